@@ -1,8 +1,9 @@
 //! Application state.
 //!
-//! [`App`] owns the feed item list, de-duplication set, scroll position, and
-//! status message.  It is the single source of truth that the UI reads from
-//! and that input / polling code writes to.
+//! [`App`] owns the feed item list, de-duplication set, read/unread state,
+//! filter rules, per-source health, scroll positions, and status message.
+//! It is the single source of truth that the UI reads from and that
+//! input / polling code writes to.
 //!
 //! ## For contributors
 //!
@@ -12,26 +13,143 @@
 //! * All public methods are covered by the test suite at the bottom of
 //!   this file.  Please add tests for any new behaviour.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+use ratatui::text::Line;
 use ratatui::widgets::ListState;
 
+use crate::export::Format;
+use crate::filter::{Rule, Rules, Verdict};
+use crate::poll::{Command, SourceStatus};
+use crate::search::Search;
 use crate::source::FeedItem;
 
+/// Which view fills the main area of the screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum View {
+    /// The item list and preview pane.
+    #[default]
+    Items,
+    /// The source health dashboard.
+    Sources,
+    /// The filter rules, for viewing and editing.
+    Rules,
+}
+
 /// Core application state.
 ///
 /// Created once in `main()` and passed by mutable reference to the input
 /// handler, poll-message processor, and UI renderer each tick.
 pub struct App {
-    /// De-duplicated feed items in reverse-chronological order (newest first).
+    /// De-duplicated feed items in reverse-chronological order (newest
+    /// first), pinned items ahead of the rest.  Items hidden by a rule are
+    /// not in here.
     pub items: Vec<FeedItem>,
 
+    /// Items hidden by a rule, kept so that changing the rules can bring
+    /// them back.
+    hidden: Vec<FeedItem>,
+
+    /// Filter rules applied to every item.
+    rules: Rules,
+
+    /// What the rules said about each item, by ID.  Items no rule matched
+    /// are left out.
+    verdicts: HashMap<String, Verdict>,
+
+    /// Set when the rules are edited at runtime; cleared by
+    /// [`App::take_rules_change`], after which `main()` saves them.
+    rules_changed: bool,
+
+    /// Selected row in the rules view.
+    pub rules_state: ListState,
+
+    /// Text typed at the "add rule" prompt, while it is open.
+    pub rule_input: Option<String>,
+
+    /// Why the rule last entered at the prompt was rejected.
+    pub rule_error: Option<String>,
+
     /// Set of item IDs we have already seen, used for O(1) de-duplication.
     seen: HashSet<String>,
 
+    /// URL of the source each item polled this run came from, by item ID.
+    /// Items loaded from history are not in here.
+    item_sources: HashMap<String, String>,
+
+    /// Descriptions as plain text, by item ID, for searching.  Converted
+    /// once as items arrive rather than on every keystroke and frame.
+    plain: HashMap<String, String>,
+
+    /// IDs of items the user has read.
+    read: HashSet<String>,
+
+    /// Read-state changes (`id`, `is_read`) not yet handed to the history
+    /// store; drained by [`App::take_read_changes`].
+    read_changes: Vec<(String, bool)>,
+
+    /// Link the user asked to open; drained by [`App::take_open_request`]
+    /// and launched by `main()` via [`crate::opener`].
+    open_request: Option<String>,
+
+    /// Text typed at the "export to" prompt, while it is open.
+    pub export_input: Option<String>,
+
+    /// Why the file name last entered at the export prompt was rejected.
+    pub export_error: Option<String>,
+
+    /// Directory the export prompt starts in, if not the current one.
+    pub export_dir: Option<PathBuf>,
+
+    /// File the user asked to export to; drained by
+    /// [`App::take_export_request`] and written by `main()` via
+    /// [`crate::export`].
+    export_request: Option<PathBuf>,
+
+    /// Latest state of each source, in the order first reported.
+    pub sources: Vec<SourceStatus>,
+
+    /// Commands for the poller; drained by [`App::take_poll_commands`]
+    /// and sent by `main()` through [`crate::poll::Control`].
+    poll_commands: Vec<Command>,
+
+    /// Whether scheduled polling is paused.
+    pub paused: bool,
+
+    /// Which view is shown.
+    pub view: View,
+
     /// Ratatui list widget selection state (tracks the highlighted row).
     pub list_state: ListState,
 
+    /// When set, new items arriving while the newest item is selected move
+    /// the selection up to the new newest item.
+    pub follow_newest: bool,
+
+    /// Whether the preview pane below the list is shown.
+    pub show_preview: bool,
+
+    /// First visible line of the preview pane.  Reset when the selection
+    /// changes; kept within the content by [`App::fit_preview`].
+    pub preview_scroll: u16,
+
+    /// The selected item's description as converted for the preview pane,
+    /// with the item ID and pane width it was converted for.  Kept by
+    /// [`crate::ui`] so that it is converted once, not on every frame.
+    pub preview_lines: Option<(String, u16, Vec<Line<'static>>)>,
+
+    /// The current search, if any: its hits are highlighted in the list
+    /// and `n` / `N` jump between them.
+    pub search: Option<Search>,
+
+    /// Whether the search prompt is open and taking keystrokes.
+    pub searching: bool,
+
+    /// ID of the item selected when the search prompt was opened;
+    /// incremental search starts from here, and cancelling returns here.
+    search_origin: Option<String>,
+
     /// Set to `true` when the user requests quit; checked by the main loop.
     pub quit: bool,
 
@@ -45,8 +163,35 @@ impl App {
     pub fn new() -> Self {
         Self {
             items: Vec::new(),
+            hidden: Vec::new(),
+            rules: Rules::default(),
+            verdicts: HashMap::new(),
+            rules_changed: false,
+            rules_state: ListState::default(),
+            rule_input: None,
+            rule_error: None,
             seen: HashSet::new(),
+            item_sources: HashMap::new(),
+            plain: HashMap::new(),
+            read: HashSet::new(),
+            read_changes: Vec::new(),
+            open_request: None,
+            export_input: None,
+            export_error: None,
+            export_dir: None,
+            export_request: None,
+            sources: Vec::new(),
+            poll_commands: Vec::new(),
+            paused: false,
+            view: View::default(),
             list_state: ListState::default(),
+            follow_newest: false,
+            show_preview: true,
+            preview_scroll: 0,
+            preview_lines: None,
+            search: None,
+            searching: false,
+            search_origin: None,
             quit: false,
             status: "Starting\u{2026}".into(), // "Starting…"
         }
@@ -57,19 +202,622 @@ impl App {
     /// Merge newly-fetched items into the list.
     ///
     /// * Duplicates (by `id`) are silently skipped.
+    /// * Items a rule hides are kept aside rather than listed.
     /// * The list is re-sorted after insertion so that the newest item is
-    ///   always at index 0.
-    pub fn merge_items(&mut self, new_items: Vec<FeedItem>) {
+    ///   always at index 0 (after any pinned items).
+    /// * The selection stays on the same item, even if its index changed,
+    ///   unless [`App::follow_newest`] is set and the newest item was
+    ///   selected, in which case the new newest item is selected.
+    ///
+    /// Returns the items that were actually added, hidden or not, so the
+    /// caller can write them through to the history [`crate::store::Store`].
+    pub fn merge_items(&mut self, new_items: Vec<FeedItem>) -> Vec<FeedItem> {
+        let selected = self.list_state.selected();
+        let selected_id = self.selected_item().map(|i| i.id.clone());
+
+        let mut added = Vec::new();
         for item in new_items {
             if self.seen.insert(item.id.clone()) {
-                self.items.push(item);
+                if let Some(description) = &item.description {
+                    self.plain.insert(item.id.clone(), crate::html::to_plain(description));
+                }
+                added.push(item.clone());
+                self.file(item);
+            }
+        }
+        if added.is_empty() {
+            return added;
+        }
+        self.sort_items();
+
+        if self.follow_newest && selected == Some(0) {
+            // Selected programmatically, so neither marked read nor
+            // counted as the user moving the cursor.
+            if selected_id.as_deref() != Some(self.items[0].id.as_str()) {
+                self.preview_scroll = 0;
+            }
+            self.list_state.select(Some(0));
+        } else if let Some(id) = selected_id {
+            let i = self.items.iter().position(|item| item.id == id);
+            self.list_state.select(i);
+        }
+        added
+    }
+
+    /// [`App::merge_items`] for items just fetched from the source at
+    /// `url`, remembering where they came from for [`App::refresh_selected`].
+    pub fn merge_polled(&mut self, url: &str, items: Vec<FeedItem>) -> Vec<FeedItem> {
+        for item in &items {
+            self.item_sources.insert(item.id.clone(), url.to_string());
+        }
+        self.merge_items(items)
+    }
+
+    /// Put `item` in the list or with the hidden items, as the rules say.
+    fn file(&mut self, item: FeedItem) {
+        let verdict = self.rules.apply(&item);
+        if verdict != Verdict::default() {
+            self.verdicts.insert(item.id.clone(), verdict);
+        }
+        if verdict.hide {
+            self.hidden.push(item);
+        } else {
+            self.items.push(item);
+        }
+    }
+
+    /// Pinned items first, then reverse-chronological via `FeedItem`'s
+    /// `Ord` impl.
+    fn sort_items(&mut self) {
+        let verdicts = &self.verdicts;
+        let pinned = |item: &FeedItem| verdicts.get(&item.id).is_some_and(|v| v.pin);
+        self.items.sort_by(|a, b| pinned(b).cmp(&pinned(a)).then_with(|| a.cmp(b)));
+    }
+
+    /// Turn [`App::follow_newest`] on or off.
+    pub fn toggle_follow(&mut self) {
+        self.follow_newest = !self.follow_newest;
+    }
+
+    // -- source health -------------------------------------------------------
+
+    /// Record the latest state reported by the poller for a source.
+    pub fn update_source(&mut self, status: SourceStatus) {
+        let existing = self
+            .sources
+            .iter_mut()
+            .find(|s| s.name == status.name && s.url == status.url);
+        match existing {
+            Some(s) => *s = status,
+            None => self.sources.push(status),
+        }
+    }
+
+    /// Sources whose most recent fetch failed.
+    pub fn failing_sources(&self) -> Vec<&SourceStatus> {
+        self.sources
+            .iter()
+            .filter(|s| s.health.consecutive_failures > 0)
+            .collect()
+    }
+
+    /// Number of items in the list polled this run from the source at
+    /// `url`.  Counting by URL keeps feeds that share a name apart.
+    pub fn item_count(&self, url: &str) -> usize {
+        self.items
+            .iter()
+            .filter(|i| self.item_sources.get(&i.id).is_some_and(|u| u == url))
+            .count()
+    }
+
+    /// Switch between the item list and the source dashboard.
+    pub fn toggle_sources_view(&mut self) {
+        self.view = match self.view {
+            View::Sources => View::Items,
+            _ => View::Sources,
+        };
+    }
+
+    // -- filter rules --------------------------------------------------------
+
+    /// Replace the filter rules and re-file every item under them.  The
+    /// selection stays on the same item if it is still listed.
+    pub fn set_rules(&mut self, rules: Rules) {
+        let selected_id = self.selected_item().map(|i| i.id.clone());
+        self.rules = rules;
+        self.verdicts.clear();
+        let all: Vec<FeedItem> = self.items.drain(..).chain(self.hidden.drain(..)).collect();
+        for item in all {
+            self.file(item);
+        }
+        self.sort_items();
+        let i = selected_id.and_then(|id| self.items.iter().position(|item| item.id == id));
+        self.list_state.select(i);
+    }
+
+    /// Every item, including those a rule hides.
+    pub fn all_items(&self) -> impl Iterator<Item = &FeedItem> {
+        self.items.iter().chain(&self.hidden)
+    }
+
+    /// The filter rules, in order.
+    pub fn rules(&self) -> &[Rule] {
+        self.rules.rules()
+    }
+
+    /// What the rules say about `item`.
+    pub fn verdict(&self, item: &FeedItem) -> Verdict {
+        self.verdicts.get(&item.id).copied().unwrap_or_default()
+    }
+
+    /// Number of items hidden by a rule.
+    pub fn hidden_count(&self) -> usize {
+        self.hidden.len()
+    }
+
+    /// Switch between the item list and the rules view.
+    pub fn toggle_rules_view(&mut self) {
+        self.view = match self.view {
+            View::Rules => View::Items,
+            _ => View::Rules,
+        };
+        if self.rules_state.selected().is_none() && !self.rules().is_empty() {
+            self.rules_state.select(Some(0));
+        }
+    }
+
+    /// Open the "add rule" prompt.
+    pub fn start_rule_input(&mut self) {
+        self.rule_input = Some(String::new());
+        self.rule_error = None;
+    }
+
+    /// Add `c` to the rule being typed.
+    pub fn rule_input_char(&mut self, c: char) {
+        if let Some(input) = &mut self.rule_input {
+            input.push(c);
+        }
+    }
+
+    /// Delete the last character of the rule being typed.
+    pub fn rule_input_backspace(&mut self) {
+        if let Some(input) = &mut self.rule_input {
+            input.pop();
+        }
+    }
+
+    /// Close the "add rule" prompt without adding anything.
+    pub fn cancel_rule_input(&mut self) {
+        self.rule_input = None;
+        self.rule_error = None;
+    }
+
+    /// Add the rule typed at the prompt (see [`Rule`]'s text form).  If it
+    /// is invalid the prompt stays open and [`App::rule_error`] says why.
+    pub fn confirm_rule_input(&mut self) {
+        let Some(text) = self.rule_input.take() else {
+            return;
+        };
+        let rules = text.parse::<Rule>().and_then(|rule| {
+            let mut rules = self.rules().to_vec();
+            rules.push(rule);
+            Rules::new(rules)
+        });
+        match rules {
+            Ok(rules) => {
+                self.rule_error = None;
+                self.edit_rules(rules);
+                self.rules_state.select(Some(self.rules().len() - 1));
+                self.status = format!("Added rule: {}", text.trim());
+            }
+            Err(e) => {
+                self.rule_error = Some(format!("{e:#}"));
+                self.rule_input = Some(text);
+            }
+        }
+    }
+
+    /// Delete the rule selected in the rules view.
+    pub fn delete_selected_rule(&mut self) {
+        let Some(i) = self.rules_state.selected().filter(|&i| i < self.rules().len()) else {
+            return;
+        };
+        let mut rules = self.rules().to_vec();
+        let removed = rules.remove(i);
+        // Removing a rule can't make the others invalid.
+        if let Ok(rules) = Rules::new(rules) {
+            self.edit_rules(rules);
+        }
+        let len = self.rules().len();
+        self.rules_state.select((len > 0).then(|| i.min(len - 1)));
+        self.status = format!("Deleted rule: {removed}");
+    }
+
+    /// Move the rules view selection down one row.
+    pub fn select_next_rule(&mut self) {
+        let len = self.rules().len();
+        if len > 0 {
+            let i = self.rules_state.selected().map_or(0, |i| (i + 1).min(len - 1));
+            self.rules_state.select(Some(i));
+        }
+    }
+
+    /// Move the rules view selection up one row.
+    pub fn select_previous_rule(&mut self) {
+        if !self.rules().is_empty() {
+            let i = self.rules_state.selected().map_or(0, |i| i.saturating_sub(1));
+            self.rules_state.select(Some(i));
+        }
+    }
+
+    /// Take the rules if they were edited since the last call, for saving.
+    pub fn take_rules_change(&mut self) -> Option<Vec<Rule>> {
+        std::mem::take(&mut self.rules_changed).then(|| self.rules().to_vec())
+    }
+
+    fn edit_rules(&mut self, rules: Rules) {
+        self.set_rules(rules);
+        self.rules_changed = true;
+    }
+
+    // -- poller control ------------------------------------------------------
+
+    /// Ask for every source to be fetched now.
+    pub fn refresh_all(&mut self) {
+        self.poll_commands.push(Command::RefreshAll);
+        self.status = "Refreshing all sources".into();
+    }
+
+    /// Ask for the selected item's source to be fetched now.  For items
+    /// from history, whose feed is not known, every source with the item's
+    /// source name is.
+    pub fn refresh_selected(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let name = item.source_name.clone();
+        let urls: Vec<String> = match self.item_sources.get(&item.id) {
+            Some(url) => vec![url.clone()],
+            None => self.sources.iter().filter(|s| s.name == name).map(|s| s.url.clone()).collect(),
+        };
+        self.status = format!("Refreshing {name}");
+        self.poll_commands.extend(urls.into_iter().map(Command::Refresh));
+    }
+
+    /// Pause or resume scheduled polling.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        let (command, status) = if self.paused {
+            (Command::Pause, "Polling paused")
+        } else {
+            (Command::Resume, "Polling resumed")
+        };
+        self.poll_commands.push(command);
+        self.status = status.into();
+    }
+
+    /// Take the poller commands queued since the last call, oldest first.
+    pub fn take_poll_commands(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.poll_commands)
+    }
+
+    // -- read / unread -------------------------------------------------------
+
+    /// Whether the user has read `item`.  Items muted by a rule count as
+    /// read.
+    pub fn is_read(&self, item: &FeedItem) -> bool {
+        self.read.contains(&item.id) || self.verdict(item).mute
+    }
+
+    /// Restore read state loaded from history, without recording changes.
+    pub fn restore_read(&mut self, ids: impl IntoIterator<Item = String>) {
+        self.read.extend(ids);
+    }
+
+    /// Apply read-state changes made elsewhere (another client of the
+    /// daemon), without recording them.
+    pub fn apply_read_changes(&mut self, changes: &[(String, bool)]) {
+        for (id, read) in changes {
+            if *read {
+                self.read.insert(id.clone());
+            } else {
+                self.read.remove(id);
             }
         }
-        self.items.sort(); // reverse-chronological via FeedItem's Ord impl
+    }
+
+    /// IDs of the items the user has read, in no particular order.
+    pub fn read_ids(&self) -> impl Iterator<Item = &String> {
+        self.read.iter()
+    }
+
+    /// Flip the read state of the selected item.
+    pub fn toggle_read(&mut self) {
+        if let Some(id) = self.selected_item().map(|i| i.id.clone()) {
+            let read = !self.read.contains(&id);
+            self.set_read(&id, read);
+        }
+    }
+
+    /// Mark every item in the list as read.
+    pub fn mark_all_read(&mut self) {
+        let ids: Vec<String> = self.items.iter().map(|i| i.id.clone()).collect();
+        for id in ids {
+            self.set_read(&id, true);
+        }
+    }
+
+    /// Number of unread items in the list.
+    pub fn unread_count(&self) -> usize {
+        self.items.iter().filter(|i| !self.is_read(i)).count()
+    }
+
+    /// Unread item counts per source, sorted by source name.  Sources with
+    /// nothing unread are omitted.
+    pub fn unread_by_source(&self) -> Vec<(&str, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for item in self.items.iter().filter(|i| !self.is_read(i)) {
+            *counts.entry(&item.source_name).or_default() += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Take the read-state changes made since the last call, oldest first.
+    pub fn take_read_changes(&mut self) -> Vec<(String, bool)> {
+        std::mem::take(&mut self.read_changes)
+    }
+
+    fn set_read(&mut self, id: &str, read: bool) {
+        let changed = if read {
+            self.read.insert(id.to_string())
+        } else {
+            self.read.remove(id)
+        };
+        if changed {
+            self.read_changes.push((id.to_string(), read));
+        }
+    }
+
+    // -- opening links -------------------------------------------------------
+
+    /// Ask for the selected item's link to be opened.  Sets the status
+    /// message instead if the item has no link, or one that is not an
+    /// `http` or `https` URL (see [`crate::opener::web_url`]).
+    pub fn request_open(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        match item.link.as_deref().map(|link| (link, crate::opener::web_url(link))) {
+            Some((_, Some(url))) => self.open_request = Some(url.into()),
+            Some((link, None)) => self.status = format!("Not opening {link}: not an http(s) link"),
+            None => self.status = "Item has no link".into(),
+        }
+    }
+
+    /// Take the pending open request, if any.
+    pub fn take_open_request(&mut self) -> Option<String> {
+        self.open_request.take()
+    }
+
+    // -- export --------------------------------------------------------------
+
+    /// Open the "export to" prompt with a file name for today's date.
+    pub fn start_export(&mut self) {
+        let name = format!("livescroll-rss-{}.md", chrono::Utc::now().date_naive());
+        let path = match &self.export_dir {
+            Some(dir) => dir.join(name),
+            None => PathBuf::from(name),
+        };
+        self.export_input = Some(path.display().to_string());
+        self.export_error = None;
+    }
+
+    /// Add `c` to the file name being typed.
+    pub fn export_input_char(&mut self, c: char) {
+        if let Some(input) = &mut self.export_input {
+            input.push(c);
+        }
+    }
+
+    /// Delete the last character of the file name being typed.
+    pub fn export_input_backspace(&mut self) {
+        if let Some(input) = &mut self.export_input {
+            input.pop();
+        }
+    }
+
+    /// Close the export prompt without exporting.
+    pub fn cancel_export(&mut self) {
+        self.export_input = None;
+        self.export_error = None;
+    }
+
+    /// Ask for the items to be exported to the file named at the prompt.
+    /// The format comes from its extension; if there is none the prompt
+    /// stays open and [`App::export_error`] says why.
+    pub fn confirm_export(&mut self) {
+        let Some(text) = self.export_input.take() else {
+            return;
+        };
+        let path = PathBuf::from(text.trim());
+        if Format::from_path(&path).is_none() {
+            self.export_error = Some("end the name in .md, .csv, .json or .html".into());
+            self.export_input = Some(text);
+            return;
+        }
+        self.export_error = None;
+        self.export_request = Some(path);
+    }
+
+    /// Reopen the export prompt on `path`, saying why it was not used.
+    pub fn reject_export(&mut self, path: &Path, reason: &str) {
+        self.export_input = Some(path.display().to_string());
+        self.export_error = Some(reason.into());
+    }
+
+    /// Take the pending export request, if any, with the items to export:
+    /// the search hits while there is a search, else every listed item.
+    pub fn take_export_request(&mut self) -> Option<(PathBuf, Vec<FeedItem>)> {
+        let path = self.export_request.take()?;
+        let items = match &self.search {
+            Some(search) => self.items.iter().filter(|i| self.is_hit(search, i)).cloned().collect(),
+            None => self.items.clone(),
+        };
+        Some((path, items))
+    }
+
+    // -- preview pane --------------------------------------------------------
+
+    /// Show or hide the preview pane.
+    pub fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+    }
+
+    /// Scroll the preview pane down by `lines`.
+    pub fn scroll_preview_down(&mut self, lines: u16) {
+        self.preview_scroll = self.preview_scroll.saturating_add(lines);
+    }
+
+    /// Scroll the preview pane up by `lines`.
+    pub fn scroll_preview_up(&mut self, lines: u16) {
+        self.preview_scroll = self.preview_scroll.saturating_sub(lines);
+    }
+
+    /// Stop scrolling past the last of the preview's `lines`, as wrapped
+    /// for the pane's current size (see [`crate::ui::preview_line_count`]).
+    pub fn fit_preview(&mut self, lines: usize) {
+        let max = u16::try_from(lines.saturating_sub(1)).unwrap_or(u16::MAX);
+        self.preview_scroll = self.preview_scroll.min(max);
+    }
+
+    // -- search --------------------------------------------------------------
+
+    /// Open the search prompt with an empty query, keeping the previous
+    /// search's regex setting.
+    pub fn start_search(&mut self) {
+        let regex = self.search.as_ref().is_some_and(|s| s.regex);
+        self.search = Some(Search::new("", regex));
+        self.searching = true;
+        self.search_origin = self.selected_item().map(|i| i.id.clone());
+    }
+
+    /// Add `c` to the query and jump to the first hit.
+    pub fn search_input(&mut self, c: char) {
+        self.edit_search(|query, regex| (format!("{query}{c}"), regex));
+    }
+
+    /// Delete the last character of the query.
+    pub fn search_backspace(&mut self) {
+        self.edit_search(|query, regex| {
+            let mut query = query.to_string();
+            query.pop();
+            (query, regex)
+        });
+    }
+
+    /// Switch the query between literal text and a regular expression.
+    pub fn toggle_search_regex(&mut self) {
+        self.edit_search(|query, regex| (query.to_string(), !regex));
+    }
+
+    /// Close the prompt, keeping the search for highlighting and `n` /
+    /// `N`.  An empty query clears the search.
+    pub fn confirm_search(&mut self) {
+        self.searching = false;
+        match self.search.as_ref().map(|s| s.query.as_str()) {
+            None | Some("") => self.search = None,
+            Some(query) => {
+                self.status = match self.match_count() {
+                    0 => format!("No matches for {query}"),
+                    n => format!("{n} matches for {query}"),
+                };
+                if let Some(i) = self.list_state.selected() {
+                    self.select(i);
+                }
+            }
+        }
+    }
+
+    /// Close the prompt, drop the search and return to where it started.
+    pub fn cancel_search(&mut self) {
+        self.searching = false;
+        self.search = None;
+        self.select_quietly(self.origin_index());
+    }
+
+    /// Number of items the current search matches.
+    pub fn match_count(&self) -> usize {
+        let Some(search) = &self.search else { return 0 };
+        self.items.iter().filter(|i| self.is_hit(search, i)).count()
+    }
+
+    /// Whether `search` matches `item`'s title or description.
+    fn is_hit(&self, search: &Search, item: &FeedItem) -> bool {
+        search.matches(&item.title, self.plain.get(&item.id).map(String::as_str))
+    }
+
+    /// Jump to the next hit below the cursor, wrapping around to the top.
+    pub fn search_next(&mut self) {
+        self.jump_to_match(false);
+    }
+
+    /// Jump to the previous hit above the cursor, wrapping around to the
+    /// bottom.
+    pub fn search_previous(&mut self) {
+        self.jump_to_match(true);
+    }
+
+    /// Select the nearest hit after the cursor (before, if `backwards`),
+    /// wrapping around.
+    fn jump_to_match(&mut self, backwards: bool) {
+        let Some(search) = &self.search else { return };
+        let len = self.items.len();
+        let current = self.list_state.selected();
+        let found = (1..=len)
+            .map(|off| match (current, backwards) {
+                (Some(i), false) => (i + off) % len,
+                (Some(i), true) => (i + len - off % len) % len,
+                (None, false) => off - 1,
+                (None, true) => len - off,
+            })
+            .find(|&i| self.is_hit(search, &self.items[i]));
+        match found {
+            Some(i) => self.select(i),
+            None => self.status = format!("No matches for {}", search.query),
+        }
+    }
+
+    /// Rebuild the search from `edit(query, regex)`, then move the
+    /// selection to the first hit at or after the origin without marking
+    /// anything read, or back to the origin if nothing matches.
+    fn edit_search(&mut self, edit: impl FnOnce(&str, bool) -> (String, bool)) {
+        let Some(search) = &self.search else { return };
+        let (query, regex) = edit(&search.query, search.regex);
+        let search = Search::new(&query, regex);
+
+        let len = self.items.len();
+        let origin = self.origin_index();
+        let start = origin.unwrap_or(0);
+        let hit = (0..len)
+            .map(|off| (start + off) % len)
+            .find(|&i| self.is_hit(&search, &self.items[i]));
+        self.search = Some(search);
+        self.select_quietly(hit.or(origin));
+    }
+
+    /// Where the search prompt was opened, if that item is still listed.
+    fn origin_index(&self) -> Option<usize> {
+        let id = self.search_origin.as_ref()?;
+        self.items.iter().position(|i| &i.id == id)
     }
 
     // -- list navigation -----------------------------------------------------
 
+    /// The item under the selection cursor, if any.
+    pub fn selected_item(&self) -> Option<&FeedItem> {
+        self.items.get(self.list_state.selected()?)
+    }
+
     /// Move the selection cursor down by one row.
     pub fn select_next(&mut self) {
         if self.items.is_empty() {
@@ -79,7 +827,7 @@ impl App {
             Some(i) => (i + 1).min(self.items.len() - 1),
             None => 0,
         };
-        self.list_state.select(Some(i));
+        self.select(i);
     }
 
     /// Move the selection cursor up by one row.
@@ -91,22 +839,51 @@ impl App {
             Some(i) => i.saturating_sub(1),
             None => 0,
         };
-        self.list_state.select(Some(i));
+        self.select(i);
     }
 
     /// Jump the selection cursor to the first item.
     pub fn select_first(&mut self) {
         if !self.items.is_empty() {
-            self.list_state.select(Some(0));
+            self.select(0);
         }
     }
 
     /// Jump the selection cursor to the last item.
     pub fn select_last(&mut self) {
         if !self.items.is_empty() {
-            self.list_state.select(Some(self.items.len() - 1));
+            self.select(self.items.len() - 1);
+        }
+    }
+
+    /// Jump to the next unread item below the cursor, wrapping around to
+    /// the top.  Does nothing if everything is read.
+    pub fn select_next_unread(&mut self) {
+        let len = self.items.len();
+        let start = self.list_state.selected().map_or(0, |i| i + 1);
+        if let Some(i) = (0..len)
+            .map(|off| (start + off) % len)
+            .find(|&i| !self.is_read(&self.items[i]))
+        {
+            self.select(i);
         }
     }
+
+    /// Select row `i`; selecting an item marks it read.
+    fn select(&mut self, i: usize) {
+        self.select_quietly(Some(i));
+        if let Some(id) = self.items.get(i).map(|item| item.id.clone()) {
+            self.set_read(&id, true);
+        }
+    }
+
+    /// Move the selection without marking anything read.
+    fn select_quietly(&mut self, i: Option<usize>) {
+        if self.list_state.selected() != i {
+            self.preview_scroll = 0;
+        }
+        self.list_state.select(i);
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -117,20 +894,11 @@ impl App {
 mod tests {
     use super::*;
     use chrono::{TimeZone, Utc};
+    use crate::poll::Health;
+    use crate::testutil::make_item;
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
 
-    fn make_item(id: &str, title: &str, published: Option<chrono::DateTime<Utc>>) -> FeedItem {
-        FeedItem {
-            id: id.to_string(),
-            title: title.to_string(),
-            description: None,
-            link: None,
-            published,
-            source_name: "test".to_string(),
-        }
-    }
-
     fn sample_items() -> Vec<FeedItem> {
         vec![
             make_item("1", "Old", Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())),
@@ -178,6 +946,17 @@ mod tests {
         assert!(app.items.iter().any(|i| i.id == "new"));
     }
 
+    #[test]
+    fn merge_returns_only_new_items() {
+        let mut app = App::new();
+        let added = app.merge_items(vec![make_item("a", "A", None)]);
+        assert_eq!(added.len(), 1);
+
+        let added = app.merge_items(vec![make_item("a", "A", None), make_item("b", "B", None)]);
+        let ids: Vec<_> = added.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["b"]);
+    }
+
     #[test]
     fn merge_handles_empty_input() {
         let mut app = App::new();
@@ -280,32 +1059,246 @@ mod tests {
         assert_eq!(app.list_state.selected(), Some(2));
     }
 
-    // -- rendering (smoke tests) ---------------------------------------------
+    // -- search --------------------------------------------------------------
+
+    fn type_query(app: &mut App, query: &str) {
+        app.start_search();
+        for c in query.chars() {
+            app.search_input(c);
+        }
+    }
 
     #[test]
-    fn draw_does_not_panic_with_no_items() {
+    fn incremental_search_moves_without_marking_read() {
         let mut app = App::new();
-        let backend = TestBackend::new(80, 24);
-        let mut terminal = Terminal::new(backend).unwrap();
-        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        app.merge_items(sample_items());
+        type_query(&mut app, "mi");
+        assert_eq!(app.selected_item().unwrap().title, "Mid");
+        assert_eq!(app.unread_count(), 3, "passing over hits doesn't read them");
+
+        app.search_input('x');
+        assert_eq!(app.list_state.selected(), None, "no hit: back to the origin");
+        app.search_backspace();
+        app.confirm_search();
+        assert!(!app.searching);
+        assert_eq!(app.match_count(), 1);
+        assert_eq!(app.unread_count(), 2, "the final hit is read");
     }
 
     #[test]
-    fn draw_does_not_panic_with_items() {
+    fn search_next_and_previous_wrap() {
         let mut app = App::new();
-        app.merge_items(sample_items());
-        app.select_first();
+        app.merge_items(sample_items()); // New, Mid, Old
+        type_query(&mut app, "[eo]");
+        app.toggle_search_regex();
+        app.confirm_search();
+        assert_eq!(app.match_count(), 2, "New and Old");
+        assert_eq!(app.selected_item().unwrap().title, "New");
 
-        let backend = TestBackend::new(80, 24);
-        let mut terminal = Terminal::new(backend).unwrap();
-        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        app.search_next();
+        assert_eq!(app.selected_item().unwrap().title, "Old");
+        app.search_next();
+        assert_eq!(app.selected_item().unwrap().title, "New");
+        app.search_previous();
+        assert_eq!(app.selected_item().unwrap().title, "Old");
     }
 
     #[test]
-    fn draw_status_shows_item_count() {
+    fn cancel_search_restores_selection() {
         let mut app = App::new();
         app.merge_items(sample_items());
-        app.status = "OK".to_string();
+        app.select_last();
+        type_query(&mut app, "new");
+        assert_eq!(app.list_state.selected(), Some(0));
+        app.cancel_search();
+        assert_eq!(app.list_state.selected(), Some(2));
+        assert!(app.search.is_none());
+
+        app.search_next();
+        assert_eq!(app.list_state.selected(), Some(2), "no search, n does nothing");
+    }
+
+    #[test]
+    fn search_matches_description_text() {
+        let mut app = App::new();
+        let mut items = sample_items();
+        items[0].description = Some("<p>About <b>crabs</b></p>".into());
+        app.merge_items(items);
+        type_query(&mut app, "about crabs");
+        app.confirm_search();
+        assert_eq!(app.match_count(), 1);
+        assert_eq!(app.selected_item().unwrap().title, "Old");
+
+        type_query(&mut app, "<p>");
+        assert_eq!(app.match_count(), 0, "markup is not searched");
+    }
+
+    #[test]
+    fn empty_search_clears_highlights() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        type_query(&mut app, "old");
+        app.confirm_search();
+        assert!(app.search.is_some());
+        app.start_search();
+        app.confirm_search();
+        assert!(app.search.is_none());
+    }
+
+    #[test]
+    fn draw_highlights_search_hits() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        type_query(&mut app, "ld");
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains(" /ld  1 matches"), "prompt replaces the status bar");
+
+        let hit = buf
+            .content()
+            .iter()
+            .filter(|c| c.modifier.contains(ratatui::style::Modifier::REVERSED))
+            .map(|c| c.symbol())
+            .collect::<String>();
+        assert_eq!(hit, "ld");
+    }
+
+    // -- read / unread -------------------------------------------------------
+
+    #[test]
+    fn items_start_unread() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        assert_eq!(app.unread_count(), 3);
+    }
+
+    #[test]
+    fn selecting_marks_read_and_records_change() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+
+        assert!(app.is_read(&app.items[0]));
+        assert_eq!(app.unread_count(), 2);
+        assert_eq!(app.take_read_changes(), vec![("3".to_string(), true)]);
+        assert!(app.take_read_changes().is_empty(), "changes are drained");
+
+        app.select_first();
+        assert!(app.take_read_changes().is_empty(), "no-op re-read not recorded");
+    }
+
+    #[test]
+    fn toggle_read_marks_unread_again() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+        app.toggle_read();
+
+        assert!(!app.is_read(&app.items[0]));
+        assert_eq!(
+            app.take_read_changes(),
+            vec![("3".to_string(), true), ("3".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn toggle_read_without_selection_is_noop() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.toggle_read();
+        assert_eq!(app.unread_count(), 3);
+    }
+
+    #[test]
+    fn mark_all_read_clears_unread() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.mark_all_read();
+        assert_eq!(app.unread_count(), 0);
+        assert_eq!(app.take_read_changes().len(), 3);
+    }
+
+    #[test]
+    fn read_state_from_elsewhere_is_not_recorded() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.restore_read(["1".to_string()]);
+        assert_eq!(app.unread_count(), 2);
+        assert!(app.take_read_changes().is_empty());
+
+        app.apply_read_changes(&[("1".to_string(), false), ("2".to_string(), true)]);
+        let read: Vec<&String> = app.read_ids().collect();
+        assert_eq!(read, ["2"]);
+        assert!(app.take_read_changes().is_empty(), "made elsewhere, so not recorded");
+    }
+
+    #[test]
+    fn unread_by_source_counts_per_source() {
+        let mut app = App::new();
+        let mut items = sample_items();
+        items[0].source_name = "b".into();
+        app.merge_items(items);
+        app.restore_read(["2".to_string()]);
+        assert_eq!(app.unread_by_source(), vec![("b", 1), ("test", 1)]);
+    }
+
+    #[test]
+    fn select_next_unread_skips_read_and_wraps() {
+        let mut app = App::new();
+        app.merge_items(sample_items()); // ids in display order: 3, 2, 1
+        app.restore_read(["2".to_string()]);
+
+        app.select_next_unread();
+        assert_eq!(app.list_state.selected(), Some(0));
+        app.select_next_unread();
+        assert_eq!(app.list_state.selected(), Some(2), "skips read item");
+
+        // Make the top item unread again and park the cursor at the bottom.
+        app.select_first();
+        app.toggle_read();
+        app.list_state.select(Some(2));
+        app.select_next_unread();
+        assert_eq!(app.list_state.selected(), Some(0), "wraps to the top");
+    }
+
+    #[test]
+    fn select_next_unread_when_all_read_is_noop() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.mark_all_read();
+        app.select_next_unread();
+        assert!(app.list_state.selected().is_none());
+    }
+
+    // -- rendering (smoke tests) ---------------------------------------------
+
+    #[test]
+    fn draw_does_not_panic_with_no_items() {
+        let mut app = App::new();
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+    }
+
+    #[test]
+    fn draw_does_not_panic_with_items() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+    }
+
+    #[test]
+    fn draw_status_shows_item_count() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.status = "OK".to_string();
 
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -315,4 +1308,445 @@ mod tests {
         let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
         assert!(text.contains("3 items"), "status bar should show item count");
     }
+
+    #[test]
+    fn request_open_queues_selected_link() {
+        let mut app = App::new();
+        let mut item = make_item("1", "Linked", None);
+        item.link = Some("https://example.com/1".into());
+        app.merge_items(vec![item]);
+        app.request_open();
+        assert_eq!(app.take_open_request(), None, "nothing selected");
+
+        app.select_first();
+        app.request_open();
+        assert_eq!(app.take_open_request().as_deref(), Some("https://example.com/1"));
+        assert_eq!(app.take_open_request(), None, "request is taken once");
+    }
+
+    #[test]
+    fn request_open_refuses_links_that_are_not_web_urls() {
+        for link in ["javascript:alert(1)", "--flag"] {
+            let mut app = App::new();
+            let item = FeedItem {
+                link: Some(link.into()),
+                ..make_item("1", "Sneaky", None)
+            };
+            app.merge_items(vec![item]);
+            app.select_first();
+            app.request_open();
+            assert_eq!(app.take_open_request(), None, "{link}");
+            assert_eq!(app.status, format!("Not opening {link}: not an http(s) link"));
+        }
+    }
+
+    #[test]
+    fn request_open_without_link_sets_status() {
+        let mut app = App::new();
+        app.merge_items(vec![make_item("1", "No link", None)]);
+        app.select_first();
+        app.request_open();
+        assert_eq!(app.take_open_request(), None);
+        assert_eq!(app.status, "Item has no link");
+    }
+
+    #[test]
+    fn selection_tracks_item_across_merges() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_next();
+        app.select_next(); // "2", the middle item
+        app.scroll_preview_down(4);
+
+        app.merge_items(vec![make_item(
+            "4",
+            "Newest",
+            Some(Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()),
+        )]);
+        assert_eq!(app.list_state.selected(), Some(2));
+        assert_eq!(app.selected_item().unwrap().id, "2");
+        assert_eq!(app.preview_scroll, 4, "still reading the same item");
+    }
+
+    #[test]
+    fn follow_newest_moves_selection_only_from_top() {
+        let mut app = App::new();
+        app.toggle_follow();
+        app.merge_items(sample_items());
+        app.select_first();
+
+        let newer = |id: &str, year| make_item(id, "New", Some(Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap()));
+        app.merge_items(vec![newer("4", 2027)]);
+        assert_eq!(app.selected_item().unwrap().id, "4", "followed to the new top");
+        assert!(!app.is_read(&app.items[0]), "following does not mark read");
+
+        app.select_next();
+        app.merge_items(vec![newer("5", 2028)]);
+        assert_eq!(app.selected_item().unwrap().id, "3", "not at top: selection kept");
+    }
+
+    #[test]
+    fn without_follow_top_selection_stays_on_item() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+        app.merge_items(vec![make_item(
+            "4",
+            "Newest",
+            Some(Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()),
+        )]);
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.selected_item().unwrap().id, "3");
+    }
+
+    fn source(name: &str, failures: u32) -> SourceStatus {
+        SourceStatus {
+            name: name.into(),
+            url: format!("https://{name}.example/feed"),
+            health: Health {
+                last_error: (failures > 0).then(|| "timeout".into()),
+                consecutive_failures: failures,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sources_are_tracked_by_name_and_url() {
+        let mut app = App::new();
+        app.update_source(source("a", 0));
+        app.update_source(source("b", 2));
+        assert_eq!(app.failing_sources(), [&source("b", 2)]);
+
+        app.update_source(source("b", 0));
+        assert!(app.failing_sources().is_empty());
+        assert_eq!(app.sources.len(), 2, "updates replace, not append");
+    }
+
+    #[test]
+    fn item_count_is_per_source() {
+        let mut app = App::new();
+        let (a, b) = ("https://a.example/feed", "https://b.example/feed");
+        let mut items = sample_items();
+        app.merge_polled(b, items.split_off(2));
+        app.merge_polled(a, items);
+        assert!(app.items.iter().all(|i| i.source_name == "test"), "same name");
+        assert_eq!(app.item_count(a), 2);
+        assert_eq!(app.item_count(b), 1);
+        assert_eq!(app.item_count("https://c.example/feed"), 0);
+    }
+
+    #[test]
+    fn draw_sources_view_lists_sources() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        let mut ok = source("test", 0);
+        ok.http_status = Some(304);
+        app.update_source(ok);
+        app.update_source(source("BBC", 3));
+        app.toggle_sources_view();
+        assert_eq!(app.view, View::Sources);
+
+        let backend = TestBackend::new(160, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("https://test.example/feed"));
+        assert!(text.contains("304"));
+        assert!(text.contains("timeout"));
+        assert!(!text.contains("New"), "item list is replaced by the dashboard");
+    }
+
+    #[test]
+    fn draw_status_shows_failing_source() {
+        let mut app = App::new();
+        app.update_source(source("BBC", 3));
+        let backend = TestBackend::new(160, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("BBC: timeout (3 failures)"), "status bar names the failing source");
+    }
+
+    // -- filter rules --------------------------------------------------------
+
+    fn rules(lines: &[&str]) -> Rules {
+        Rules::new(lines.iter().map(|l| l.parse().unwrap()).collect()).unwrap()
+    }
+
+    #[test]
+    fn hidden_items_are_kept_aside_and_returned() {
+        let mut app = App::new();
+        app.set_rules(rules(&["hide title:mid"]));
+        let added = app.merge_items(sample_items());
+        assert_eq!(added.len(), 3, "hidden items still go to the history");
+        let titles: Vec<_> = app.items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["New", "Old"]);
+        assert_eq!(app.hidden_count(), 1);
+
+        app.set_rules(Rules::default());
+        assert_eq!(app.items.len(), 3, "removing the rule brings it back");
+        assert_eq!(app.merge_items(sample_items()).len(), 0, "still de-duplicated");
+    }
+
+    #[test]
+    fn pinned_items_sort_first_and_selection_survives_rule_changes() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+        app.set_rules(rules(&["pin old"]));
+        let titles: Vec<_> = app.items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["Old", "New", "Mid"]);
+        assert_eq!(app.selected_item().unwrap().title, "New");
+        assert!(app.verdict(&app.items[0]).pin);
+    }
+
+    #[test]
+    fn muted_items_count_as_read() {
+        let mut app = App::new();
+        app.set_rules(rules(&["mute new"]));
+        app.merge_items(sample_items());
+        assert_eq!(app.unread_count(), 2);
+        assert!(app.take_read_changes().is_empty(), "read state itself is untouched");
+    }
+
+    #[test]
+    fn rules_are_edited_at_runtime() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.toggle_rules_view();
+        assert_eq!(app.view, View::Rules);
+
+        app.start_rule_input();
+        for c in "hide /(/".chars() {
+            app.rule_input_char(c);
+        }
+        app.confirm_rule_input();
+        assert!(app.rule_error.is_some());
+        assert_eq!(app.rule_input.as_deref(), Some("hide /(/"), "prompt stays open to fix it");
+        assert!(app.take_rules_change().is_none());
+
+        app.cancel_rule_input();
+        app.start_rule_input();
+        for c in "hide old".chars() {
+            app.rule_input_char(c);
+        }
+        app.confirm_rule_input();
+        assert_eq!(app.rule_input, None);
+        assert_eq!(app.hidden_count(), 1);
+        let saved = app.take_rules_change().unwrap();
+        assert_eq!(saved[0].to_string(), "hide old");
+        assert!(app.take_rules_change().is_none(), "taken once");
+
+        app.delete_selected_rule();
+        assert!(app.rules().is_empty());
+        assert_eq!(app.hidden_count(), 0);
+        assert_eq!(app.take_rules_change(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn draw_rules_view_and_highlight() {
+        let mut app = App::new();
+        app.set_rules(rules(&["highlight:green mid", "hide old"]));
+        app.merge_items(sample_items());
+        let backend = TestBackend::new(100, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let green: String = buf
+            .content()
+            .iter()
+            .filter(|c| c.fg == ratatui::style::Color::Green)
+            .map(|c| c.symbol())
+            .collect();
+        assert!(green.contains("Mid"));
+
+        app.toggle_rules_view();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("1 items hidden"));
+        assert!(text.contains("highlight:green mid"));
+    }
+
+    // -- export --------------------------------------------------------------
+
+    #[test]
+    fn export_prompt_requests_search_hits() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.export_dir = Some(PathBuf::from("/tmp/digests"));
+        app.start_export();
+        let suggested = app.export_input.clone().unwrap();
+        assert!(suggested.starts_with("/tmp/digests/livescroll-rss-") && suggested.ends_with(".md"));
+
+        app.export_input = Some("digest.txt".into());
+        app.confirm_export();
+        assert!(app.export_error.is_some());
+        assert!(app.take_export_request().is_none());
+        assert_eq!(app.export_input.as_deref(), Some("digest.txt"), "prompt stays open to fix it");
+
+        for _ in 0..3 {
+            app.export_input_backspace();
+        }
+        for c in "csv".chars() {
+            app.export_input_char(c);
+        }
+        app.confirm_export();
+        assert_eq!(app.export_input, None);
+        let (path, items) = app.take_export_request().unwrap();
+        assert_eq!((path, items.len()), (PathBuf::from("digest.csv"), 3));
+        assert!(app.take_export_request().is_none(), "taken once");
+
+        app.start_search();
+        for c in "mid".chars() {
+            app.search_input(c);
+        }
+        app.confirm_search();
+        app.start_export();
+        app.confirm_export();
+        let (_, items) = app.take_export_request().unwrap();
+        assert_eq!(items.iter().map(|i| i.title.as_str()).collect::<Vec<_>>(), ["Mid"]);
+    }
+
+    // -- poller control ------------------------------------------------------
+
+    #[test]
+    fn refresh_queues_commands() {
+        let mut app = App::new();
+        app.refresh_selected();
+        assert!(app.take_poll_commands().is_empty(), "nothing selected");
+
+        app.merge_polled("https://a.example/feed", sample_items());
+        app.select_first();
+        app.refresh_selected();
+        app.refresh_all();
+        assert_eq!(
+            app.take_poll_commands(),
+            [Command::Refresh("https://a.example/feed".into()), Command::RefreshAll]
+        );
+        assert!(app.take_poll_commands().is_empty(), "commands are taken once");
+    }
+
+    #[test]
+    fn refresh_selected_picks_the_items_feed() {
+        let mut app = App::new();
+        for url in ["https://example.com/a", "https://example.com/b"] {
+            app.update_source(SourceStatus {
+                name: "test".into(),
+                url: url.into(),
+                ..SourceStatus::default()
+            });
+        }
+        let mut items = sample_items();
+        let from_history = items.pop().unwrap();
+        app.merge_polled("https://example.com/b", items);
+        app.merge_items(vec![from_history]);
+
+        app.select_last();
+        assert_eq!(app.selected_item().unwrap().title, "Old");
+        app.refresh_selected();
+        assert_eq!(app.take_poll_commands(), [Command::Refresh("https://example.com/b".into())]);
+
+        app.select_first();
+        app.refresh_selected();
+        assert_eq!(app.take_poll_commands().len(), 2, "feed unknown: every source named test");
+    }
+
+    #[test]
+    fn toggle_pause_alternates_and_shows_in_status_bar() {
+        let mut app = App::new();
+        app.toggle_pause();
+        assert!(app.paused);
+        let backend = TestBackend::new(200, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("[paused]"));
+
+        app.toggle_pause();
+        assert!(!app.paused);
+        assert_eq!(app.take_poll_commands(), [Command::Pause, Command::Resume]);
+    }
+
+    #[test]
+    fn preview_scroll_resets_on_selection_change() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+        app.scroll_preview_down(5);
+        app.scroll_preview_up(2);
+        assert_eq!(app.preview_scroll, 3);
+
+        app.select_first();
+        assert_eq!(app.preview_scroll, 3, "same item keeps its scroll");
+        app.select_next();
+        assert_eq!(app.preview_scroll, 0);
+        app.scroll_preview_up(1);
+        assert_eq!(app.preview_scroll, 0, "saturates at the top");
+    }
+
+    #[test]
+    fn preview_scroll_reaches_the_end_of_wrapped_text() {
+        let mut app = App::new();
+        let mut item = make_item("1", &"Long headline ".repeat(20), None);
+        item.description = Some(format!("<p>{}end.</p>", "word ".repeat(200)));
+        app.merge_items(vec![item]);
+        app.select_first();
+        let area = ratatui::layout::Rect::new(0, 0, 40, 24);
+        let lines = crate::ui::preview_line_count(&mut app, area).unwrap();
+        let (id, width, _) = app.preview_lines.clone().unwrap();
+        assert_eq!((id.as_str(), width), ("1", 36), "the converted description is kept for drawing");
+
+        app.scroll_preview_down(u16::MAX);
+        app.fit_preview(lines);
+        let backend = TestBackend::new(40, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("end."), "the last line can be scrolled to");
+        assert_eq!(usize::from(app.preview_scroll), lines - 1);
+    }
+
+    #[test]
+    fn draw_renders_preview_of_selected_item() {
+        let mut app = App::new();
+        let mut item = make_item("1", "Headline", None);
+        item.description = Some("<p>Body <b>text</b></p>".into());
+        app.merge_items(vec![item]);
+        app.select_first();
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("Body text"), "preview shows the rendered description");
+
+        app.toggle_preview();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(!text.contains("Body text"), "hidden preview is not drawn");
+    }
+
+    #[test]
+    fn draw_status_shows_unread_counts() {
+        let mut app = App::new();
+        app.merge_items(sample_items());
+        app.select_first();
+
+        let backend = TestBackend::new(120, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| crate::ui::draw(&mut app, f)).unwrap();
+
+        let buf = terminal.backend().buffer().clone();
+        let text: String = buf.content().iter().map(|c| c.symbol().chars().next().unwrap_or(' ')).collect();
+        assert!(text.contains("2 unread (test 2)"), "status bar should show unread counts");
+    }
 }
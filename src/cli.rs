@@ -0,0 +1,245 @@
+//! Command-line arguments.
+//!
+//! Parsed with [`clap`]'s derive API.  Anything that can also be set in the
+//! config file is merged into [`Config`] by [`Cli::apply`], so the rest of
+//! the program only ever looks at the config.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::{Config, FeedConfig};
+use crate::export::{DateRange, Format, Grouping};
+use crate::headless::{Output, DEFAULT_TEMPLATE};
+use crate::source::FeedKind;
+
+/// Feed shown when neither the command line nor the config file names one.
+pub const DEFAULT_FEED_URL: &str = "https://feeds.bbci.co.uk/news/rss.xml";
+
+/// Live-updating RSS / Atom / JSON Feed reader for the terminal.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Feed URLs to poll.  When given, these replace the feeds listed in
+    /// the config file.
+    #[arg(value_name = "FEED_URL")]
+    pub urls: Vec<String>,
+
+    /// Path to the config file [default: ~/.config/livescroll-rss/config.toml].
+    #[arg(short, long, value_name = "FILE", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Format of the feeds given on the command line.
+    #[arg(short = 't', long = "type", value_enum, default_value_t = FeedKind::Rss)]
+    pub kind: FeedKind,
+
+    /// Add the feeds in an OPML file to the config file, then exit.
+    #[arg(long, value_name = "FILE", conflicts_with = "export_opml")]
+    pub import_opml: Option<PathBuf>,
+
+    /// Print the feed list as OPML to stdout, then exit.
+    #[arg(long)]
+    pub export_opml: bool,
+
+    /// Don't start the TUI; print each new item to stdout as it arrives.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Line printed per item in headless mode.  Placeholders: {title},
+    /// {link}, {source}, {published}, {author}, {id}, {description}.
+    #[arg(long, value_name = "TEMPLATE", requires = "headless", default_value = DEFAULT_TEMPLATE)]
+    pub template: String,
+
+    /// In headless mode, print items as NDJSON instead.
+    #[arg(long, requires = "headless", conflicts_with = "template")]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Things to do instead of starting the TUI.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Export the items in the history, with filter rules applied, then exit.
+    Export(ExportArgs),
+    /// Poll feeds and keep the history in the background, for `attach` to
+    /// connect to.  Runs until stopped with SIGINT or SIGTERM.
+    Daemon(SocketArgs),
+    /// Start the TUI on a running daemon instead of polling feeds itself.
+    Attach(SocketArgs),
+}
+
+/// Arguments of `livescroll-rss daemon` and `attach`.
+#[derive(Debug, Args)]
+pub struct SocketArgs {
+    /// Unix socket to listen on or connect to [default: the config file's,
+    /// else $XDG_RUNTIME_DIR/livescroll-rss.sock].
+    #[arg(long, value_name = "PATH")]
+    pub socket: Option<PathBuf>,
+}
+
+/// Arguments of `livescroll-rss export`.
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// File to write [default: stdout].
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Output format [default: from the output file's extension, else markdown].
+    #[arg(short, long, value_enum)]
+    pub format: Option<Format>,
+
+    /// How to group items [default: the config file's, else none].
+    #[arg(short, long, value_enum)]
+    pub group: Option<Grouping>,
+
+    /// Only items published on or after this day (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<NaiveDate>,
+
+    /// Only items published on or before this day (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<NaiveDate>,
+}
+
+impl ExportArgs {
+    /// The export options these arguments ask for, given the configured
+    /// grouping.
+    pub fn options(&self, grouping: Grouping) -> crate::export::Options {
+        crate::export::Options {
+            format: self
+                .format
+                .or_else(|| self.output.as_deref().and_then(Format::from_path))
+                .unwrap_or(Format::Markdown),
+            grouping: self.group.unwrap_or(grouping),
+            range: DateRange {
+                since: self.since,
+                until: self.until,
+            },
+        }
+    }
+}
+
+impl Cli {
+    /// How to print items in headless mode.
+    pub fn output(&self) -> Output {
+        if self.json {
+            Output::Json
+        } else {
+            Output::Template(self.template.clone())
+        }
+    }
+
+    /// Merge command-line settings into `config`.
+    pub fn apply(&self, config: &mut Config) {
+        if !self.urls.is_empty() {
+            config.feeds = self
+                .urls
+                .iter()
+                .map(|url| FeedConfig {
+                    kind: self.kind,
+                    ..FeedConfig::new(url)
+                })
+                .collect();
+        }
+        if let Some(Command::Daemon(args) | Command::Attach(args)) = &self.command {
+            if let Some(socket) = &args.socket {
+                config.daemon.socket = Some(socket.clone());
+            }
+        }
+    }
+}
+
+/// Poll [`DEFAULT_FEED_URL`] if `config` lists no feeds.  Only done before
+/// polling, so that `--export-opml` never exports a feed nobody added.
+pub fn use_default_feed(config: &mut Config) {
+    if config.feeds.is_empty() {
+        config.feeds.push(FeedConfig::new(DEFAULT_FEED_URL));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from(args: &[&str], file: &str) -> Config {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let mut config = Config::parse(file).unwrap();
+        cli.apply(&mut config);
+        config
+    }
+
+    #[test]
+    fn no_feeds_anywhere_uses_default() {
+        let mut config = config_from(&["livescroll-rss"], "");
+        assert!(config.feeds.is_empty(), "not added by apply, so not exported");
+        use_default_feed(&mut config);
+        assert_eq!(config.feeds, vec![FeedConfig::new(DEFAULT_FEED_URL)]);
+        use_default_feed(&mut config);
+        assert_eq!(config.feeds.len(), 1);
+    }
+
+    #[test]
+    fn config_feeds_used_without_urls() {
+        let config = config_from(&["livescroll-rss"], "[[feed]]\nurl = \"https://a/\"\n");
+        assert_eq!(config.feeds.len(), 1);
+        assert_eq!(config.feeds[0].url, "https://a/");
+    }
+
+    #[test]
+    fn headless_output_flags() {
+        let cli = Cli::try_parse_from(["livescroll-rss", "--headless"]).unwrap();
+        assert_eq!(cli.output(), Output::Template(DEFAULT_TEMPLATE.into()));
+        let cli = Cli::try_parse_from(["livescroll-rss", "--headless", "--json"]).unwrap();
+        assert_eq!(cli.output(), Output::Json);
+        assert!(Cli::try_parse_from(["livescroll-rss", "--json"]).is_err(), "needs --headless");
+        assert!(Cli::try_parse_from(["livescroll-rss", "--headless", "--json", "--template", "{title}"]).is_err());
+    }
+
+    #[test]
+    fn export_subcommand() {
+        let cli = Cli::try_parse_from(["livescroll-rss", "-c", "x.toml", "export", "-o", "week.html", "--since", "2025-01-06"])
+            .unwrap();
+        let Some(Command::Export(args)) = &cli.command else {
+            panic!("expected export, got {:?}", cli.command);
+        };
+        let options = args.options(Grouping::Day);
+        assert_eq!((options.format, options.grouping), (Format::Html, Grouping::Day));
+        assert_eq!(options.range.since, NaiveDate::from_ymd_opt(2025, 1, 6));
+
+        let cli = Cli::try_parse_from(["livescroll-rss", "export", "-f", "csv", "-g", "source", "-o", "x.md"]).unwrap();
+        let Some(Command::Export(args)) = &cli.command else { panic!() };
+        let options = args.options(Grouping::Day);
+        assert_eq!((options.format, options.grouping), (Format::Csv, Grouping::Source));
+        assert!(Cli::try_parse_from(["livescroll-rss", "export", "--since", "last week"]).is_err());
+        assert!(Cli::try_parse_from(["livescroll-rss", "https://x/"]).unwrap().command.is_none());
+        let cli = Cli::try_parse_from(["livescroll-rss", "export", "-c", "x.toml"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("x.toml")), "--config also goes after the subcommand");
+    }
+
+    #[test]
+    fn socket_flag_overrides_config() {
+        let file = "[daemon]\nsocket = \"/from/config\"\n";
+        let config = config_from(&["livescroll-rss", "attach", "--socket", "/from/flag"], file);
+        assert_eq!(config.daemon.socket, Some(PathBuf::from("/from/flag")));
+        let config = config_from(&["livescroll-rss", "daemon"], file);
+        assert_eq!(config.daemon.socket, Some(PathBuf::from("/from/config")));
+    }
+
+    #[test]
+    fn urls_replace_config_feeds() {
+        let config = config_from(
+            &["livescroll-rss", "--type", "atom", "https://x/", "https://y/"],
+            "[[feed]]\nurl = \"https://a/\"\n",
+        );
+        let urls: Vec<_> = config.feeds.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(urls, ["https://x/", "https://y/"]);
+        assert!(config.feeds.iter().all(|f| f.kind == FeedKind::Atom));
+    }
+}
@@ -0,0 +1,680 @@
+//! User configuration.
+//!
+//! [`Config`] mirrors the TOML file at `~/.config/livescroll-rss/config.toml`
+//! (or wherever `--config` points).  It is loaded once in `main()` and
+//! turned into the `Vec<Box<dyn DataSource>>` handed to [`crate::poll`].
+//!
+//! ```toml
+//! opener = "firefox --new-tab %s"
+//! poll_interval = 300
+//! max_parallel = 16
+//!
+//! [[feed]]
+//! label = "BBC"
+//! url = "https://feeds.bbci.co.uk/news/rss.xml"
+//!
+//! [[feed]]
+//! label = "Rust releases"
+//! url = "https://github.com/rust-lang/rust/releases.atom"
+//! type = "atom"
+//! poll_interval = 3600
+//!
+//! [history]
+//! retention_days = 30
+//!
+//! [server]
+//! enabled = true
+//! listen = "127.0.0.1:8080"
+//!
+//! [[rule]]
+//! keyword = "football"
+//! action = "hide"
+//!
+//! [notify]
+//! osc = "777"
+//! command = "notify-send %t %b"
+//!
+//! [daemon]
+//! socket = "/run/user/1000/feeds.sock"
+//!
+//! [[webhook]]
+//! url = "https://chat.example.com/hooks/abc"
+//! ```
+//!
+//! ## For contributors
+//!
+//! * Every field must have a sensible default (`#[serde(default)]`) so that
+//!   an empty or missing file is a valid configuration.
+//! * Command-line flags live in [`crate::cli`]; this module does not know
+//!   about them.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::num::NonZeroUsize;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::export::Grouping;
+use crate::filter::{Filter, Rule};
+use crate::notify::{NotifyWith, Osc};
+use crate::source::FeedKind;
+
+/// Top-level configuration file contents.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Feeds to poll, in the order they appear in the file.
+    #[serde(rename = "feed")]
+    pub feeds: Vec<FeedConfig>,
+
+    /// On-disk item history.
+    pub history: HistoryConfig,
+
+    /// Re-publishing the merged item list over HTTP.
+    pub server: ServerConfig,
+
+    /// Exporting items to a file.
+    pub export: ExportConfig,
+
+    /// How notifications are delivered.
+    pub notify: NotifyConfig,
+
+    /// Running in the background; see [`crate::daemon`].
+    pub daemon: DaemonConfig,
+
+    /// Webhooks to POST new items to.
+    #[serde(rename = "webhook")]
+    pub webhooks: Vec<WebhookConfig>,
+
+    /// Command used to open item links; see [`crate::opener`].  Defaults
+    /// to `$BROWSER`, then the platform's opener.
+    pub opener: Option<String>,
+
+    /// Start with "follow newest" on: when the newest item is selected,
+    /// keep the selection on the newest item as new ones arrive.
+    pub follow_newest: bool,
+
+    /// Seconds between polls of each feed, unless the feed sets its own
+    /// `poll_interval`.
+    pub poll_interval: u64,
+
+    /// Most feeds fetched at the same time.
+    pub max_parallel: usize,
+
+    /// Most feeds fetched at the same time from any one host.
+    pub max_per_host: usize,
+
+    /// Filter rules, in the order they appear in the file; see
+    /// [`crate::filter`].
+    #[serde(rename = "rule")]
+    pub rules: Vec<Rule>,
+
+    /// The file this was loaded from, or would have been if it existed.
+    /// Rules edited at runtime are saved here.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            feeds: Vec::new(),
+            history: HistoryConfig::default(),
+            server: ServerConfig::default(),
+            export: ExportConfig::default(),
+            notify: NotifyConfig::default(),
+            daemon: DaemonConfig::default(),
+            webhooks: Vec::new(),
+            opener: None,
+            follow_newest: false,
+            poll_interval: 60,
+            max_parallel: 8,
+            max_per_host: 2,
+            rules: Vec::new(),
+            path: None,
+        }
+    }
+}
+
+/// The `[history]` table.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Whether to load and save item history at all.
+    pub enabled: bool,
+
+    /// History file location [default: see [`crate::store::default_path`]].
+    pub path: Option<PathBuf>,
+
+    /// Forget items first seen more than this many days ago.  `0` keeps
+    /// everything.
+    pub retention_days: u32,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+            retention_days: 30,
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// The retention window, or `None` to keep everything.
+    pub fn retention(&self) -> Option<chrono::Duration> {
+        (self.retention_days > 0).then(|| chrono::Duration::days(self.retention_days.into()))
+    }
+}
+
+/// The `[server]` table; see [`crate::serve`].
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Whether to run the feed server at all.
+    pub enabled: bool,
+
+    /// Address to listen on.
+    pub listen: String,
+
+    /// Title of the served feeds.
+    pub title: String,
+
+    /// Paths of the RSS 2.0, Atom and JSON Feed documents.  An empty path
+    /// leaves that format out.
+    pub rss_path: String,
+    pub atom_path: String,
+    pub json_path: String,
+
+    /// Most items in each document, newest first.  `0` serves them all.
+    pub max_items: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "127.0.0.1:8080".into(),
+            title: "livescroll-rss".into(),
+            rss_path: "/rss.xml".into(),
+            atom_path: "/atom.xml".into(),
+            json_path: "/feed.json".into(),
+            max_items: 100,
+        }
+    }
+}
+
+/// The `[export]` table; see [`crate::export`].
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// How exports are grouped unless `--group` says otherwise.
+    pub group: Grouping,
+
+    /// Directory the `E` key suggests saving to [default: the current
+    /// directory].
+    pub dir: Option<PathBuf>,
+}
+
+/// The `[daemon]` table; see [`crate::daemon`].
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    /// Unix socket the daemon listens on and `attach` connects to
+    /// [default: `$XDG_RUNTIME_DIR/livescroll-rss.sock`].
+    pub socket: Option<PathBuf>,
+}
+
+impl DaemonConfig {
+    /// The configured socket, or the default if there is one on this
+    /// platform.
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        self.socket.clone().or_else(|| {
+            // Without a runtime directory, fall back to the history's.
+            dirs::runtime_dir()
+                .map(|d| d.join("livescroll-rss.sock"))
+                .or_else(|| Some(dirs::data_dir()?.join("livescroll-rss").join("daemon.sock")))
+        })
+    }
+}
+
+/// The `[notify]` table; see [`crate::notify`].  Which items notify is up
+/// to `notify` on feeds and rules.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Ring the terminal bell.
+    pub bell: bool,
+
+    /// Terminal escape sequence for a desktop notification.
+    pub osc: Osc,
+
+    /// Command to run for each notification, e.g. `notify-send`.  `%t` is
+    /// replaced by the heading and `%b` by the body; without either, both
+    /// are appended.
+    pub command: Option<String>,
+
+    /// Most notifications in any minute.  Items arriving beyond that are
+    /// summed up in the next one.
+    pub per_minute: NonZeroUsize,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            bell: true,
+            osc: Osc::None,
+            command: None,
+            per_minute: NonZeroUsize::new(4).unwrap(),
+        }
+    }
+}
+
+/// One `[[webhook]]` table; see [`crate::webhook`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    /// Where to POST.
+    pub url: String,
+
+    /// Body for one item, with `{placeholder}`s as in `--template`
+    /// [default: the item as JSON].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Send the items from each fetch in one request, as a JSON array.
+    #[serde(default)]
+    pub batch: bool,
+
+    /// Times to retry a failed request.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+
+    /// Extra request headers, e.g. `Authorization`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+
+    /// Only send items matching one of these.  Without any, every new item
+    /// is sent.
+    #[serde(default, rename = "filter", skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<Filter>,
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+/// One `[[feed]]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FeedConfig {
+    /// Feed URL.
+    pub url: String,
+
+    /// Short name shown as the `[source]` tag.  Defaults to the URL's host.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Feed format; see [`FeedKind`].
+    #[serde(default, rename = "type")]
+    pub kind: FeedKind,
+
+    /// Folder the feed belongs to, e.g. from an OPML import.  Nested
+    /// folders are separated by `/`; `\/` is a `/` within a name.  Only
+    /// used to write the folders back out in [`crate::opml::write`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    /// Seconds between polls, overriding the top-level `poll_interval`.
+    /// Feeds that ask to be polled less often (`<ttl>` etc.) are.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<u64>,
+
+    /// Send a notification for every new item from this feed; see
+    /// [`crate::notify`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub notify: bool,
+
+    /// How this feed's items notify, where it differs from `[notify]`.
+    #[serde(default, skip_serializing_if = "NotifyWith::is_empty")]
+    pub notify_with: NotifyWith,
+}
+
+impl FeedConfig {
+    /// A feed with no label and the default type.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            label: None,
+            kind: FeedKind::default(),
+            group: None,
+            poll_interval: None,
+            notify: false,
+            notify_with: NotifyWith::default(),
+        }
+    }
+
+    /// The configured label, or the URL's host name if none was given.
+    pub fn label(&self) -> String {
+        self.label.clone().unwrap_or_else(|| {
+            reqwest::Url::parse(&self.url)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+                .unwrap_or_else(|| self.url.clone())
+        })
+    }
+}
+
+impl Config {
+    /// Load the configuration.
+    ///
+    /// With an explicit `path` the file must exist.  Otherwise the default
+    /// location is tried, and a missing file yields an empty [`Config`].
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(p) => (p.to_path_buf(), true),
+            None => match default_path() {
+                Some(p) => (p, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let config = match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).with_context(|| format!("in {}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Self::default(),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        Ok(Self {
+            path: Some(path),
+            ..config
+        })
+    }
+
+    /// How often to poll `feed`: its own `poll_interval`, else the global
+    /// one.  At least one second.
+    pub fn poll_interval(&self, feed: &FeedConfig) -> std::time::Duration {
+        let secs = feed.poll_interval.unwrap_or(self.poll_interval);
+        std::time::Duration::from_secs(secs.max(1))
+    }
+
+    /// Parse configuration from TOML text.
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+/// Append `[[feed]]` tables for `feeds` to the config file at `path`.
+///
+/// The file is appended to rather than rewritten so that the user's
+/// comments and formatting survive.  Missing parent directories and the
+/// file itself are created.
+pub fn append_feeds(path: &Path, feeds: &[FeedConfig]) -> Result<()> {
+    use std::io::Write;
+
+    #[derive(Serialize)]
+    struct FeedTables<'a> {
+        feed: &'a [FeedConfig],
+    }
+
+    let tables = toml::to_string(&FeedTables { feed: feeds })?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {}", path.display()))?;
+    writeln!(file, "\n{tables}")?;
+    Ok(())
+}
+
+/// Replace the `[[rule]]` tables in the config file at `path` with `rules`.
+///
+/// Everything else in the file, comments included, is kept.  The file is
+/// created if it does not exist.
+pub fn save_rules(path: &Path, rules: &[Rule]) -> Result<()> {
+    #[derive(Serialize)]
+    struct RuleTables<'a> {
+        rule: &'a [Rule],
+    }
+
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let mut doc: toml_edit::DocumentMut = text.parse().with_context(|| format!("in {}", path.display()))?;
+    doc.remove("rule");
+    if !rules.is_empty() {
+        let tables: toml_edit::DocumentMut = toml::to_string(&RuleTables { rule: rules })?.parse()?;
+        if let Some(item) = tables.get("rule") {
+            doc.insert("rule", item.clone());
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, doc.to_string()).with_context(|| format!("writing {}", path.display()))
+}
+
+/// `$XDG_CONFIG_HOME/livescroll-rss/config.toml` (or the platform equivalent).
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("livescroll-rss").join("config.toml"))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_is_valid() {
+        let config = Config::parse("").unwrap();
+        assert!(config.feeds.is_empty());
+    }
+
+    #[test]
+    fn parses_feed_tables() {
+        let config = Config::parse(
+            r#"
+[[feed]]
+label = "BBC"
+url = "https://feeds.bbci.co.uk/news/rss.xml"
+
+[[feed]]
+url = "https://example.com/feed.json"
+type = "json"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.feeds.len(), 2);
+        assert_eq!(config.feeds[0].label(), "BBC");
+        assert_eq!(config.feeds[0].kind, FeedKind::Rss, "type defaults to rss");
+        assert_eq!(config.feeds[1].kind, FeedKind::Json);
+    }
+
+    #[test]
+    fn history_defaults_and_overrides() {
+        let config = Config::parse("").unwrap();
+        assert!(config.history.enabled);
+        assert_eq!(config.history.retention(), Some(chrono::Duration::days(30)));
+
+        let config = Config::parse("[history]\nretention_days = 0\n").unwrap();
+        assert_eq!(config.history.retention(), None, "0 keeps everything");
+    }
+
+    #[test]
+    fn opener_is_optional() {
+        assert_eq!(Config::parse("").unwrap().opener, None);
+        let config = Config::parse("opener = \"w3m %s\"\n").unwrap();
+        assert_eq!(config.opener.as_deref(), Some("w3m %s"));
+        assert!(!config.follow_newest);
+    }
+
+    #[test]
+    fn poll_interval_per_feed_overrides_global() {
+        let config = Config::parse(
+            "poll_interval = 300\n[[feed]]\nurl = \"a\"\n[[feed]]\nurl = \"b\"\npoll_interval = 0\n",
+        )
+        .unwrap();
+        let secs = |i: usize| config.poll_interval(&config.feeds[i]).as_secs();
+        assert_eq!(secs(0), 300);
+        assert_eq!(secs(1), 1, "clamped to one second");
+        assert_eq!(Config::parse("").unwrap().poll_interval, 60);
+    }
+
+    #[test]
+    fn fetch_limits_default_and_override() {
+        let config = Config::parse("").unwrap();
+        assert_eq!((config.max_parallel, config.max_per_host), (8, 2));
+        let config = Config::parse("max_parallel = 32\nmax_per_host = 4\n").unwrap();
+        assert_eq!((config.max_parallel, config.max_per_host), (32, 4));
+    }
+
+    #[test]
+    fn server_is_off_by_default() {
+        let config = Config::parse("").unwrap();
+        assert!(!config.server.enabled);
+        assert_eq!(config.server.rss_path, "/rss.xml");
+
+        let config = Config::parse("[server]\nenabled = true\njson_path = \"\"\n").unwrap();
+        assert!(config.server.enabled);
+        assert_eq!((config.server.listen.as_str(), config.server.json_path.as_str()), ("127.0.0.1:8080", ""));
+    }
+
+    #[test]
+    fn export_grouping() {
+        assert_eq!(Config::parse("").unwrap().export.group, Grouping::None);
+        let config = Config::parse("[export]\ngroup = \"day\"\n").unwrap();
+        assert_eq!(config.export.group, Grouping::Day);
+        assert!(Config::parse("[export]\ngroup = \"week\"\n").is_err());
+    }
+
+    #[test]
+    fn notify_defaults_and_overrides() {
+        let config = Config::parse("[[feed]]\nurl = \"a\"\n").unwrap();
+        assert!(!config.feeds[0].notify);
+        assert!(config.notify.bell);
+        assert_eq!((config.notify.osc, config.notify.per_minute.get()), (Osc::None, 4));
+
+        let config =
+            Config::parse("[[feed]]\nurl = \"a\"\nnotify = true\n[notify]\nbell = false\nosc = \"777\"\n").unwrap();
+        assert!(config.feeds[0].notify && !config.notify.bell);
+        assert_eq!(config.notify.osc, Osc::Osc777);
+        assert!(Config::parse("[notify]\nosc = \"8\"\n").is_err());
+    }
+
+    #[test]
+    fn notify_with_on_feeds_and_rules() {
+        let text = "[[feed]]\nurl = \"a\"\nnotify = true\nnotify_with = { bell = false, command = \"x %t\" }\n\
+                    [[rule]]\nkeyword = \"k\"\naction = \"notify\"\nnotify_with = { osc = \"9\" }\n";
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.feeds[0].notify_with.bell, Some(false));
+        assert_eq!(config.feeds[0].notify_with.command.as_deref(), Some("x %t"));
+        assert_eq!(config.rules[0].notify_with.osc, Some(Osc::Osc9));
+        assert!(Config::parse("[[feed]]\nurl = \"a\"\n").unwrap().feeds[0].notify_with.is_empty());
+
+        let hide = Config::parse("[[rule]]\nkeyword = \"k\"\naction = \"hide\"\nnotify_with = { bell = false }\n").unwrap();
+        assert!(crate::filter::Rules::new(hide.rules).is_err(), "only notify rules");
+        assert!(Config::parse("[notify]\nper_minute = 0\n").is_err());
+    }
+
+    #[test]
+    fn daemon_socket_override() {
+        let config = Config::parse("[daemon]\nsocket = \"/run/feeds.sock\"\n").unwrap();
+        assert_eq!(config.daemon.socket_path(), Some(PathBuf::from("/run/feeds.sock")));
+    }
+
+    #[test]
+    fn parses_webhooks() {
+        let config = Config::parse(
+            "[[webhook]]\nurl = \"https://a/\"\nbatch = true\n[webhook.headers]\nAuthorization = \"Bearer x\"\n[[webhook.filter]]\nkeyword = \"rust\"\n\n[[webhook]]\nurl = \"https://b/\"\n",
+        )
+        .unwrap();
+        let [a, b] = &config.webhooks[..] else { panic!("{:?}", config.webhooks) };
+        assert!(a.batch && !b.batch);
+        assert_eq!((a.retries, a.filters.len()), (3, 1));
+        assert_eq!(a.headers["Authorization"], "Bearer x");
+        assert!(Config::parse("[[webhook]]\nbatch = true\n").is_err(), "url is required");
+    }
+
+    #[test]
+    fn rejects_unknown_feed_type() {
+        let err = Config::parse("[[feed]]\nurl = \"x\"\ntype = \"gopher\"\n");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn label_defaults_to_host() {
+        assert_eq!(FeedConfig::new("https://www.example.com/rss").label(), "example.com");
+        assert_eq!(FeedConfig::new("not a url").label(), "not a url");
+    }
+
+    #[test]
+    fn append_feeds_keeps_existing_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sub").join("config.toml");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "# my feeds\n[[feed]]\nurl = \"https://a/\"\n").unwrap();
+
+        let new = FeedConfig {
+            label: Some("B".into()),
+            group: Some("News/Tech".into()),
+            ..FeedConfig::new("https://b/")
+        };
+        append_feeds(&path, std::slice::from_ref(&new)).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# my feeds\n"), "comments preserved");
+        assert!(!text.contains("[history]"), "only feed tables are appended");
+        let config = Config::parse(&text).unwrap();
+        assert_eq!(config.feeds.len(), 2);
+        assert_eq!(config.feeds[1], new);
+    }
+
+    #[test]
+    fn parses_rules() {
+        let config = Config::parse(
+            "[[rule]]\nkeyword = \"football\"\naction = \"hide\"\n\n[[rule]]\nregex = \"acme\"\nfield = \"title\"\naction = \"highlight\"\ncolor = \"green\"\n",
+        )
+        .unwrap();
+        let rules: Vec<String> = config.rules.iter().map(|r| r.to_string()).collect();
+        assert_eq!(rules, ["hide football", "highlight:green title:/acme/"]);
+        assert!(Config::parse("[[rule]]\nkeyword = \"x\"\naction = \"explode\"\n").is_err());
+    }
+
+    #[test]
+    fn save_rules_replaces_only_rule_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "# keep me\npoll_interval = 30\n\n[[rule]]\nkeyword = \"old\"\naction = \"hide\"\n\n[[feed]]\nurl = \"https://a/\"\n",
+        )
+        .unwrap();
+
+        save_rules(&path, &["pin acme".parse().unwrap()]).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# keep me\n"), "comments preserved");
+        let config = Config::parse(&text).unwrap();
+        assert_eq!(config.poll_interval, 30);
+        assert_eq!(config.feeds.len(), 1);
+        assert_eq!(config.rules, ["pin acme".parse::<Rule>().unwrap()]);
+
+        save_rules(&path, &[]).unwrap();
+        assert!(Config::parse(&fs::read_to_string(&path).unwrap()).unwrap().rules.is_empty());
+    }
+
+    #[test]
+    fn missing_explicit_file_is_an_error() {
+        assert!(Config::load(Some(Path::new("/nonexistent/livescroll.toml"))).is_err());
+    }
+}
@@ -0,0 +1,667 @@
+//! `daemon` and `attach`: polling and history in a long-running process,
+//! with the TUI as a client that can come and go.
+//!
+//! `livescroll-rss daemon` does everything the TUI does except drawing: it
+//! polls, keeps the history, feeds the server, webhooks and notifications,
+//! and listens on a Unix socket.  `livescroll-rss attach` starts the TUI on
+//! that socket — from any terminal or SSH session, several at once — and
+//! quitting it leaves the daemon polling.
+//!
+//! ## For contributors
+//!
+//! * The protocol is newline-delimited JSON: [`Event`]s from the daemon,
+//!   [`Request`]s from clients.  A client first gets one [`Event::Hello`]
+//!   with everything the daemon knows, then the rest as it happens.
+//! * Items are sent with hidden ones included, and each client files them
+//!   under its own rules, just as when it polls by itself.  Rules edited in
+//!   a client are saved to the config file by the client and sent to the
+//!   daemon, so that webhooks and notifications follow them, and the daemon
+//!   passes them on to the other clients.
+//! * Read-state changes and pausing go to the daemon, which acts on them
+//!   and passes them on to the other clients.
+//! * The daemon runs notification commands itself, but has no terminal for
+//!   the bell and escape sequences.  [`Event::Items`] says which items
+//!   notify, and each client sends those to its own terminal.
+//! * The daemon's loop never waits on a client: each client has a writer
+//!   thread fed through a bounded queue, and a client whose queue fills up
+//!   (or whose writes fail or time out) is disconnected.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::App;
+use crate::filter::{Rule, Rules};
+use crate::notify::Notifier;
+use crate::poll::{self, Command, PollMsg, SourceStatus};
+use crate::sinks::Sinks;
+use crate::source::FeedItem;
+use crate::store::Store;
+
+/// How long the daemon waits for requests before checking everything else.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Longest a write to one client may take.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events queued for one client before it is given up on as stuck.
+const QUEUE: usize = 256;
+
+/// Messages from the daemon to its clients.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// Everything so far, sent once as a client connects.
+    Hello {
+        items: Vec<FeedItem>,
+        read: Vec<String>,
+        sources: Vec<SourceStatus>,
+        paused: bool,
+    },
+    /// Items seen for the first time from the source at `url`, hidden ones
+    /// included, and the IDs of those that notify.
+    Items {
+        url: String,
+        items: Vec<FeedItem>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        notify: Vec<String>,
+    },
+    /// The state of one source, after every fetch attempt.
+    Status { status: SourceStatus },
+    /// Read-state changes made in another client.
+    Read { changes: Vec<(String, bool)> },
+    /// Another client paused or resumed polling.
+    Paused { paused: bool },
+    /// Another client changed the filter rules.
+    Rules { rules: Vec<Rule> },
+}
+
+/// Messages from a client to the daemon.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Pass a command on to the poller.
+    Poll { command: Command },
+    /// Save read-state changes and tell the other clients.
+    Read { changes: Vec<(String, bool)> },
+    /// Use these filter rules for the daemon's outputs.
+    Rules { rules: Vec<Rule> },
+}
+
+// ---------------------------------------------------------------------------
+// Daemon
+// ---------------------------------------------------------------------------
+
+/// The daemon's socket.  The socket file is removed when dropped.
+pub struct Listener {
+    socket: UnixListener,
+    path: PathBuf,
+}
+
+impl Listener {
+    /// Listen on `path`, replacing a socket left behind by a daemon that
+    /// did not exit cleanly.  Fails if a daemon is listening there already.
+    pub fn bind(path: &Path) -> Result<Self> {
+        match fs::symlink_metadata(path) {
+            Ok(meta) if !meta.file_type().is_socket() => bail!("{} exists and is not a socket", path.display()),
+            Ok(_) if UnixStream::connect(path).is_ok() => bail!("a daemon is already listening on {}", path.display()),
+            Ok(_) => fs::remove_file(path).with_context(|| format!("removing {}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("checking {}", path.display())),
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let socket = UnixListener::bind(path).with_context(|| format!("listening on {}", path.display()))?;
+        // Whoever can connect can read the feeds and mark items read.
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// The socket's path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// What the client threads tell the daemon's loop.
+enum Incoming {
+    Request(usize, Request),
+    /// The client hung up.
+    Gone(usize),
+}
+
+/// The daemon's side of one client.
+struct Peer {
+    /// Lines for the client's writer thread.
+    queue: mpsc::SyncSender<Arc<[u8]>>,
+    /// For hanging up, which also ends the client's threads.
+    stream: UnixStream,
+}
+
+/// The daemon's loop and the state it drives.
+struct Daemon<'a> {
+    app: &'a mut App,
+    store: &'a mut Option<Store>,
+    control: &'a poll::Control,
+    sinks: &'a mut Sinks,
+    /// The connected clients, by ID.
+    clients: HashMap<usize, Peer>,
+    next_id: usize,
+    requests: mpsc::Sender<Incoming>,
+}
+
+/// Run the daemon until a signal sets `signal`: merge and store what the
+/// poller sends, feed `sinks`, and keep every client of `listener` up to
+/// date.  Problems are reported on stderr.
+pub fn serve(
+    app: &mut App,
+    store: &mut Option<Store>,
+    rx: &mpsc::Receiver<PollMsg>,
+    control: &poll::Control,
+    sinks: &mut Sinks,
+    listener: &Listener,
+    signal: &AtomicUsize,
+) -> Result<()> {
+    let (requests, incoming) = mpsc::channel();
+    let mut daemon = Daemon {
+        app,
+        store,
+        control,
+        sinks,
+        clients: HashMap::new(),
+        next_id: 0,
+        requests,
+    };
+
+    while signal.load(Ordering::SeqCst) == 0 {
+        loop {
+            match listener.socket.accept() {
+                Ok((stream, _)) => {
+                    if let Err(e) = daemon.connect(stream) {
+                        eprintln!("livescroll-rss: error greeting a client: {e}");
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                // E.g. out of file descriptors, or the client gave up:
+                // keep polling and try again on the next tick.
+                Err(e) => {
+                    eprintln!("livescroll-rss: error accepting a client: {e}");
+                    break;
+                }
+            }
+        }
+        while let Ok(msg) = rx.try_recv() {
+            daemon.polled(msg);
+        }
+        // The daemon holds a sender, so this only ever times out.
+        let waiting: Vec<Incoming> = incoming.recv_timeout(TICK).into_iter().chain(incoming.try_iter()).collect();
+        for msg in waiting {
+            match msg {
+                Incoming::Request(id, request) => daemon.handle(id, request),
+                Incoming::Gone(id) => {
+                    daemon.clients.remove(&id);
+                }
+            }
+        }
+        daemon.sinks.tick();
+        for error in daemon.sinks.take_errors() {
+            eprintln!("livescroll-rss: error {error}");
+        }
+    }
+    Ok(())
+}
+
+impl Daemon<'_> {
+    /// Greet a new client and start its reader and writer threads.
+    fn connect(&mut self, stream: UnixStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let reader = stream.try_clone()?;
+        let mut writer = stream.try_clone()?;
+        let hello = Event::Hello {
+            items: self.app.all_items().cloned().collect(),
+            read: self.app.read_ids().cloned().collect(),
+            sources: self.app.sources.clone(),
+            paused: self.app.paused,
+        };
+        let (queue, lines) = mpsc::sync_channel::<Arc<[u8]>>(QUEUE);
+        let _ = queue.try_send(encode(&hello).into());
+
+        let id = self.next_id;
+        self.next_id += 1;
+        thread::Builder::new().name(format!("client {id} writer")).spawn(move || {
+            for line in lines {
+                if writer.write_all(&line).is_err() {
+                    // Also ends the reader thread, which reports the client gone.
+                    let _ = writer.shutdown(Shutdown::Both);
+                    break;
+                }
+            }
+        })?;
+        let requests = self.requests.clone();
+        thread::Builder::new().name(format!("client {id}")).spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                match serde_json::from_str(&line) {
+                    Ok(request) => {
+                        if requests.send(Incoming::Request(id, request)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => eprintln!("livescroll-rss: bad request from a client: {e}"),
+                }
+            }
+            let _ = requests.send(Incoming::Gone(id));
+        })?;
+        self.clients.insert(id, Peer { queue, stream });
+        Ok(())
+    }
+
+    /// Act on a message from the poller, as the TUI would, and pass it on.
+    fn polled(&mut self, msg: PollMsg) {
+        let event = match msg {
+            PollMsg::Items { url, items } => {
+                let added = self.app.merge_polled(&url, items);
+                if added.is_empty() {
+                    return;
+                }
+                if let Some(s) = self.store.as_mut() {
+                    if let Err(e) = s.append(&added) {
+                        eprintln!("livescroll-rss: error saving history: {e:#}");
+                    }
+                }
+                self.sinks.merged(self.app, &url, &added);
+                let notify = match &self.sinks.notifier {
+                    Some(notifier) => added.iter().filter(|i| notifier.wants(self.app, &url, i)).map(|i| i.id.clone()).collect(),
+                    None => Vec::new(),
+                };
+                Event::Items {
+                    url,
+                    items: added,
+                    notify,
+                }
+            }
+            PollMsg::Status(status) => {
+                if let Some(error) = status.health.last_error.as_ref().filter(|_| status.health.consecutive_failures > 0) {
+                    eprintln!("livescroll-rss: {}: {error}", status.name);
+                }
+                self.app.update_source(status.clone());
+                Event::Status { status }
+            }
+        };
+        self.broadcast(&event, None);
+    }
+
+    /// Act on a request from client `id`.
+    fn handle(&mut self, id: usize, request: Request) {
+        match request {
+            Request::Poll { command } => {
+                let paused = match command {
+                    Command::Pause => Some(true),
+                    Command::Resume => Some(false),
+                    Command::RefreshAll | Command::Refresh(_) => None,
+                };
+                self.control.send(command);
+                if let Some(paused) = paused {
+                    self.app.paused = paused;
+                    self.broadcast(&Event::Paused { paused }, Some(id));
+                }
+            }
+            Request::Read { changes } => {
+                self.app.apply_read_changes(&changes);
+                if let Some(s) = self.store.as_mut() {
+                    if let Err(e) = s.set_read(&changes) {
+                        eprintln!("livescroll-rss: error saving history: {e:#}");
+                    }
+                }
+                self.broadcast(&Event::Read { changes }, Some(id));
+            }
+            Request::Rules { rules } => match Rules::new(rules.clone()) {
+                Ok(compiled) => {
+                    self.app.set_rules(compiled);
+                    self.sinks.list_changed(self.app);
+                    self.broadcast(&Event::Rules { rules }, Some(id));
+                }
+                Err(e) => eprintln!("livescroll-rss: rules from a client: {e:#}"),
+            },
+        }
+    }
+
+    /// Queue `event` for every client but `except`, disconnecting any
+    /// that have fallen too far behind.
+    fn broadcast(&mut self, event: &Event, except: Option<usize>) {
+        let line: Arc<[u8]> = encode(event).into();
+        self.clients.retain(|&id, peer| {
+            let ok = Some(id) == except || peer.queue.try_send(Arc::clone(&line)).is_ok();
+            if !ok {
+                // Also ends the client's threads.
+                let _ = peer.stream.shutdown(Shutdown::Both);
+            }
+            ok
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Client
+// ---------------------------------------------------------------------------
+
+/// A connection to the daemon, for `attach`.
+pub struct Client {
+    stream: UnixStream,
+    events: mpsc::Receiver<Event>,
+    /// Rings the bell and sends escape sequences on this terminal for the
+    /// items the daemon says notify.
+    pub notifier: Option<Notifier>,
+}
+
+impl Client {
+    /// Connect to the daemon listening on `path`.
+    pub fn connect(path: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(path)
+            .with_context(|| format!("connecting to {} (is `livescroll-rss daemon` running?)", path.display()))?;
+        let reader = stream.try_clone()?;
+        let (tx, events) = mpsc::channel();
+        thread::Builder::new().name("daemon".into()).spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                // Skip what a newer daemon might send that we don't know.
+                let Ok(event) = serde_json::from_str(&line) else { continue };
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        })?;
+        Ok(Self {
+            stream,
+            events,
+            notifier: None,
+        })
+    }
+
+    /// Apply every event that has arrived to `app`, and notify about new
+    /// items.  Fails once the daemon has gone away.
+    pub fn receive(&mut self, app: &mut App) -> Result<()> {
+        loop {
+            let event = match self.events.try_recv() {
+                Ok(event) => event,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => bail!("lost the connection to the daemon"),
+            };
+            match event {
+                Event::Hello {
+                    items,
+                    read,
+                    sources,
+                    paused,
+                } => {
+                    app.merge_items(items);
+                    app.restore_read(read);
+                    for status in sources {
+                        app.update_source(status);
+                    }
+                    app.paused = paused;
+                    app.status = "Attached to the daemon".into();
+                }
+                Event::Items { url, items, notify } => {
+                    let count = items.len();
+                    let chosen: Vec<FeedItem> = items.iter().filter(|i| notify.contains(&i.id)).cloned().collect();
+                    app.merge_polled(&url, items);
+                    app.status = format!("New items: {count}");
+                    if let Some(notifier) = self.notifier.as_mut().filter(|_| !chosen.is_empty()) {
+                        notifier.chosen(app, &url, &chosen);
+                    }
+                }
+                Event::Status { status } => app.update_source(status),
+                Event::Read { changes } => app.apply_read_changes(&changes),
+                Event::Paused { paused } => {
+                    app.paused = paused;
+                    app.status = if paused { "Polling paused" } else { "Polling resumed" }.into();
+                }
+                Event::Rules { rules } => {
+                    // The daemon only passes on rules that compiled.
+                    if let Ok(rules) = Rules::new(rules) {
+                        app.set_rules(rules);
+                        app.status = "Rules changed in another client".into();
+                    }
+                }
+            }
+        }
+        if let Some(notifier) = &mut self.notifier {
+            notifier.tick();
+            if let Some(error) = notifier.take_errors().pop() {
+                app.status = format!("Error {error}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Send the read-state changes and poller commands queued in `app`.
+    pub fn send_changes(&mut self, app: &mut App) -> Result<()> {
+        let changes = app.take_read_changes();
+        if !changes.is_empty() {
+            self.send(&Request::Read { changes })?;
+        }
+        for command in app.take_poll_commands() {
+            self.send(&Request::Poll { command })?;
+        }
+        Ok(())
+    }
+
+    /// Send `request` to the daemon.
+    pub fn send(&mut self, request: &Request) -> Result<()> {
+        self.stream.write_all(&encode(request)).context("sending to the daemon")
+    }
+}
+
+/// `message` as one line of JSON.
+fn encode(message: &impl Serialize) -> Vec<u8> {
+    let mut line = serde_json::to_vec(message).expect("messages serialise");
+    line.push(b'\n');
+    line
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::HttpClient;
+    use crate::testutil::make_item;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn item(id: &str) -> FeedItem {
+        make_item(id, &format!("Item {id}"), None)
+    }
+
+    /// Apply events to `app` until `done` says so.
+    fn wait_for(client: &mut Client, app: &mut App, done: impl Fn(&App) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(app) {
+            assert!(Instant::now() < deadline, "timed out; status: {}", app.status);
+            client.receive(app).unwrap();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn clients_see_items_and_each_others_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let listener = Listener::bind(&path).unwrap();
+        let (poll_tx, rx) = mpsc::channel();
+        let signal = Arc::new(AtomicUsize::new(0));
+
+        let mut app = App::new();
+        app.set_rules(Rules::new(vec!["hide secret".parse().unwrap()]).unwrap());
+        app.merge_items(vec![item("old"), item("secret")]);
+        app.restore_read(["old".to_string()]);
+        let stop = Arc::clone(&signal);
+        let daemon = thread::spawn(move || {
+            let (control, _) = poll::spawn(Vec::new(), HttpClient::new(), poll::Limits::default());
+            serve(&mut app, &mut None, &rx, &control, &mut Sinks::default(), &listener, &stop).unwrap();
+            control.shutdown();
+        });
+
+        let mut first = Client::connect(&path).unwrap();
+        let mut first_app = App::new();
+        wait_for(&mut first, &mut first_app, |app| app.status == "Attached to the daemon");
+        assert_eq!(first_app.items.len(), 2, "hidden items are sent; this client has no rules");
+        assert_eq!(first_app.unread_count(), 1);
+
+        poll_tx
+            .send(PollMsg::Items {
+                url: "https://example.com/feed".into(),
+                items: vec![item("new"), item("old")],
+            })
+            .unwrap();
+        wait_for(&mut first, &mut first_app, |app| app.items.len() == 3);
+        assert_eq!(first_app.status, "New items: 1");
+
+        let mut second = Client::connect(&path).unwrap();
+        let mut second_app = App::new();
+        wait_for(&mut second, &mut second_app, |app| app.items.len() == 3);
+        first_app.mark_all_read();
+        first.send_changes(&mut first_app).unwrap();
+        wait_for(&mut second, &mut second_app, |app| app.unread_count() == 0);
+
+        signal.store(1, Ordering::SeqCst);
+        daemon.join().unwrap();
+        assert!(!path.exists(), "socket removed on exit");
+    }
+
+    #[test]
+    fn pausing_and_rules_reach_the_other_clients() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let listener = Listener::bind(&path).unwrap();
+        let (_poll_tx, rx) = mpsc::channel();
+        let signal = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::clone(&signal);
+        let daemon = thread::spawn(move || {
+            let (control, _) = poll::spawn(Vec::new(), HttpClient::new(), poll::Limits::default());
+            let mut app = App::new();
+            app.merge_items(vec![item("a")]);
+            serve(&mut app, &mut None, &rx, &control, &mut Sinks::default(), &listener, &stop).unwrap();
+            control.shutdown();
+        });
+
+        let mut first = Client::connect(&path).unwrap();
+        let mut second = Client::connect(&path).unwrap();
+        let mut first_app = App::new();
+        let mut second_app = App::new();
+        wait_for(&mut first, &mut first_app, |app| app.items.len() == 1);
+        wait_for(&mut second, &mut second_app, |app| app.items.len() == 1);
+
+        first_app.toggle_pause();
+        first.send_changes(&mut first_app).unwrap();
+        wait_for(&mut second, &mut second_app, |app| app.paused);
+
+        let rules = vec!["hide a".parse().unwrap()];
+        first.send(&Request::Rules { rules }).unwrap();
+        wait_for(&mut second, &mut second_app, |app| app.items.is_empty());
+        assert_eq!(second_app.rules().len(), 1);
+
+        signal.store(1, Ordering::SeqCst);
+        daemon.join().unwrap();
+    }
+
+    #[test]
+    fn clients_ring_their_own_terminal_for_items_that_notify() {
+        use crate::config::Config;
+        use crate::notify::Delivery;
+        use crate::testutil::Capture;
+
+        let config = Config::parse(
+            "[[feed]]\nurl = \"https://a.example/\"\nlabel = \"A\"\nnotify = true\n[notify]\nosc = \"9\"\n",
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let listener = Listener::bind(&path).unwrap();
+        let (poll_tx, rx) = mpsc::channel();
+        let signal = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::clone(&signal);
+        let daemon_terminal = Capture::default();
+        let mut sinks = Sinks {
+            notifier: Some(Notifier::with_terminal(&config, Delivery::Commands, Box::new(daemon_terminal.clone()))),
+            ..Sinks::default()
+        };
+        let daemon = thread::spawn(move || {
+            let (control, _) = poll::spawn(Vec::new(), HttpClient::new(), poll::Limits::default());
+            serve(&mut App::new(), &mut None, &rx, &control, &mut sinks, &listener, &stop).unwrap();
+            control.shutdown();
+        });
+
+        let mut client = Client::connect(&path).unwrap();
+        let terminal = Capture::default();
+        client.notifier = Some(Notifier::with_terminal(&config, Delivery::Terminal, Box::new(terminal.clone())));
+        let mut app = App::new();
+        wait_for(&mut client, &mut app, |app| app.status == "Attached to the daemon");
+
+        let loud = FeedItem {
+            source_name: "A".into(),
+            ..item("loud")
+        };
+        for (url, item) in [("https://a.example/", loud), ("https://b.example/", item("quiet"))] {
+            poll_tx
+                .send(PollMsg::Items {
+                    url: url.into(),
+                    items: vec![item],
+                })
+                .unwrap();
+        }
+        wait_for(&mut client, &mut app, |app| app.items.len() == 2);
+        assert_eq!(terminal.text(), "\x07\x1b]9;A: Item loud\x1b\\");
+        assert_eq!(daemon_terminal.text(), "", "the daemon only runs commands");
+
+        signal.store(1, Ordering::SeqCst);
+        daemon.join().unwrap();
+    }
+
+    #[test]
+    fn bind_replaces_only_stale_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let listener = Listener::bind(&path).unwrap();
+        let err = Listener::bind(&path).err().unwrap();
+        assert!(err.to_string().contains("already listening"), "{err:#}");
+
+        drop(listener);
+        assert!(!path.exists());
+
+        // A socket nobody listens on, as after a crash.
+        drop(UnixListener::bind(&path).unwrap());
+        Listener::bind(&path).unwrap();
+
+        let file = dir.path().join("config.toml");
+        fs::write(&file, "").unwrap();
+        assert!(Listener::bind(&file).is_err());
+        assert!(file.exists(), "not removed");
+    }
+}
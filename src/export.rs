@@ -0,0 +1,450 @@
+//! Exporting items to Markdown, CSV, JSON or HTML.
+//!
+//! Used by the `export` subcommand, which exports the history, and by the
+//! `E` key, which exports the items shown in the TUI (or the search hits,
+//! while there is a search).  Items can be grouped by day or by source and
+//! limited to a range of publication dates.
+//!
+//! ## For contributors
+//!
+//! * [`render`] is pure; [`write`] and [`write_new`] are the only
+//!   functions here doing I/O.
+//! * Links are made clickable in HTML and Markdown only if they are `http`
+//!   or `https`, as a feed could otherwise plant a `javascript:` link in a
+//!   shared page; other links are shown as plain text.
+//! * Days are UTC days, matching the dates shown in the item list.
+//! * To add a format, add a [`Format`] variant, an extension for it in
+//!   [`Format::from_path`], and a renderer called from [`render`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::opener::web_url;
+use crate::source::FeedItem;
+
+/// Output format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// A Markdown list of links.
+    Markdown,
+    /// One row per item.
+    Csv,
+    /// The items as JSON, in groups if grouped.
+    Json,
+    /// A standalone HTML page.
+    Html,
+}
+
+impl Format {
+    /// The format a file name's extension asks for, if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+}
+
+/// How exported items are grouped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Grouping {
+    /// One list, newest first.
+    #[default]
+    None,
+    /// By publication day, newest first.
+    Day,
+    /// By source name, alphabetically.
+    Source,
+}
+
+/// Publication dates to export, both ends included.  Undated items are
+/// only exported when neither end is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Whether `item` was published within the range.
+    pub fn contains(&self, item: &FeedItem) -> bool {
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Some(day) = item.published.map(|d| d.date_naive()) else {
+            return false;
+        };
+        self.since.is_none_or(|since| day >= since) && self.until.is_none_or(|until| day <= until)
+    }
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.since, self.until) {
+            (Some(since), Some(until)) => write!(f, "{since} to {until}"),
+            (Some(since), None) => write!(f, "since {since}"),
+            (None, Some(until)) => write!(f, "until {until}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// What to export and how.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub format: Format,
+    pub grouping: Grouping,
+    pub range: DateRange,
+}
+
+/// Render the items within `options.range`, newest first within each
+/// group.
+pub fn render(items: &[FeedItem], options: &Options) -> String {
+    let mut items: Vec<&FeedItem> = items.iter().filter(|i| options.range.contains(i)).collect();
+    // Pinned items come first in the app; a digest reads in date order.
+    items.sort();
+    let groups = group(&items, options.grouping);
+    let title = match options.range {
+        DateRange { since: None, until: None } => "Feed items".to_string(),
+        range => format!("Feed items, {range}"),
+    };
+    match options.format {
+        Format::Markdown => markdown(&title, &groups, options.grouping),
+        Format::Csv => csv(&groups),
+        Format::Json => json(&groups, options.grouping),
+        Format::Html => html(&title, &groups, options.grouping),
+    }
+}
+
+/// Render the items within `options.range` to `path`, returning how many
+/// were written.
+pub fn write(path: &Path, items: &[FeedItem], options: &Options) -> Result<usize> {
+    let count = items.iter().filter(|i| options.range.contains(i)).count();
+    fs::write(path, render(items, options)).with_context(|| format!("writing {}", path.display()))?;
+    Ok(count)
+}
+
+/// Like [`write`], but fails if `path` exists, for the `E` prompt where
+/// a typo should not replace another file.
+pub fn write_new(path: &Path, items: &[FeedItem], options: &Options) -> Result<usize> {
+    let count = items.iter().filter(|i| options.range.contains(i)).count();
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(render(items, options).as_bytes()))
+        .with_context(|| format!("writing {}", path.display()))?;
+    Ok(count)
+}
+
+/// A heading (`None` when not grouping) and the items under it.
+type Group<'a> = (Option<String>, Vec<&'a FeedItem>);
+
+fn group<'a>(items: &[&'a FeedItem], grouping: Grouping) -> Vec<Group<'a>> {
+    let key = |item: &FeedItem| match grouping {
+        Grouping::None => String::new(),
+        Grouping::Day => item.published.map(|d| d.date_naive().to_string()).unwrap_or_default(),
+        Grouping::Source => item.source_name.clone(),
+    };
+    let mut groups: BTreeMap<String, Vec<&FeedItem>> = BTreeMap::new();
+    for item in items {
+        groups.entry(key(item)).or_default().push(item);
+    }
+    let mut groups: Vec<Group> = groups.into_iter().map(|(k, items)| (Some(k), items)).collect();
+    match grouping {
+        Grouping::None => groups.iter_mut().for_each(|g| g.0 = None),
+        Grouping::Day => {
+            // Newest day first, which puts undated items (the empty key) last.
+            groups.reverse();
+            if let Some(undated) = groups.last_mut().filter(|g| g.0.as_deref() == Some("")) {
+                undated.0 = Some("No date".into());
+            }
+        }
+        Grouping::Source => {}
+    }
+    groups
+}
+
+/// Date, time and source of `item`, leaving out what the heading says.
+fn meta(item: &FeedItem, grouping: Grouping) -> String {
+    let date = match grouping {
+        Grouping::Day => item.published.map(|d| d.format("%H:%M").to_string()),
+        _ => item.published.map(|d| d.format("%Y-%m-%d %H:%M").to_string()),
+    };
+    let source = (grouping != Grouping::Source).then_some(item.source_name.as_str());
+    [source, date.as_deref()].into_iter().flatten().collect::<Vec<_>>().join(", ")
+}
+
+fn markdown(title: &str, groups: &[Group], grouping: Grouping) -> String {
+    let escape = |text: &str| {
+        text.chars().fold(String::new(), |mut out, c| {
+            if "\\`*_[]<>#".contains(c) {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+    };
+    let mut out = format!("# {title}\n");
+    for (heading, items) in groups {
+        if let Some(heading) = heading {
+            out += &format!("\n## {}\n", escape(heading));
+        }
+        out.push('\n');
+        for item in items {
+            let title = escape(&item.title);
+            // The parsed URL has `<`, `>` and whitespace percent-encoded,
+            // so it cannot end the `<...>` early.
+            let title = match item.link.as_deref().map(|link| (link, web_url(link))) {
+                Some((_, Some(url))) => format!("[{title}](<{url}>)"),
+                Some((link, None)) => format!("{title} ({})", escape(link)),
+                None => title,
+            };
+            out += &format!("- {title} — {}\n", escape(&meta(item, grouping)));
+        }
+    }
+    out
+}
+
+fn csv(groups: &[Group]) -> String {
+    let field = |text: &str| {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    };
+    let mut out = String::from("published,source,title,link,author,id\n");
+    for item in groups.iter().flat_map(|(_, items)| items) {
+        let published = item.published.map(|d| d.to_rfc3339()).unwrap_or_default();
+        let row = [
+            published.as_str(),
+            &item.source_name,
+            &item.title,
+            item.link.as_deref().unwrap_or_default(),
+            item.author.as_deref().unwrap_or_default(),
+            &item.id,
+        ];
+        out += &row.map(field).join(",");
+        out.push('\n');
+    }
+    out
+}
+
+fn json(groups: &[Group], grouping: Grouping) -> String {
+    let value = match grouping {
+        Grouping::None => serde_json::json!(groups.iter().flat_map(|(_, items)| items).collect::<Vec<_>>()),
+        _ => serde_json::json!(groups
+            .iter()
+            .map(|(heading, items)| serde_json::json!({ "group": heading, "items": items }))
+            .collect::<Vec<_>>()),
+    };
+    let mut out = serde_json::to_string_pretty(&value).expect("items serialise");
+    out.push('\n');
+    out
+}
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:50em;margin:2em auto;padding:0 1em;line-height:1.5}\
+h2{border-bottom:1px solid #ccc}li{margin:.3em 0}.meta{color:#777;font-size:.9em}";
+
+fn html(title: &str, groups: &[Group], grouping: Grouping) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{t}</title>\n\
+         <style>{STYLE}</style>\n</head>\n<body>\n<h1>{t}</h1>\n",
+        t = escape(title)
+    );
+    for (heading, items) in groups {
+        if let Some(heading) = heading {
+            out += &format!("<h2>{}</h2>\n", escape(heading));
+        }
+        out += "<ul>\n";
+        for item in items {
+            let title = match &item.link {
+                Some(link) if is_web(link) => format!("<a href=\"{}\">{}</a>", escape(link), escape(&item.title)),
+                Some(link) => format!("{} ({})", escape(&item.title), escape(link)),
+                None => escape(&item.title),
+            };
+            out += &format!("<li>{title} <span class=\"meta\">{}</span></li>\n", escape(&meta(item, grouping)));
+        }
+        out += "</ul>\n";
+    }
+    out += "</body>\n</html>\n";
+    out
+}
+
+/// Whether `link` is an `http` or `https` URL.
+fn is_web(link: &str) -> bool {
+    web_url(link).is_some()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::make_item;
+    use chrono::{TimeZone, Utc};
+
+    fn item(title: &str, source: &str, day: Option<u32>) -> FeedItem {
+        let published = day.map(|d| Utc.with_ymd_and_hms(2025, 1, d, 9, 30, 0).unwrap());
+        FeedItem {
+            link: Some(format!("https://example.com/{}", title.replace(' ', "-"))),
+            source_name: source.into(),
+            ..make_item(title, title, published)
+        }
+    }
+
+    /// Newest first, as the app keeps them.
+    fn items() -> Vec<FeedItem> {
+        vec![
+            item("Third", "BBC", Some(3)),
+            item("Second [beta]", "Acme", Some(2)),
+            item("First", "BBC", Some(1)),
+            item("Undated", "Acme", None),
+        ]
+    }
+
+    fn options(format: Format, grouping: Grouping) -> Options {
+        Options {
+            format,
+            grouping,
+            range: DateRange::default(),
+        }
+    }
+
+    #[test]
+    fn markdown_grouped_by_day() {
+        let out = render(&items(), &options(Format::Markdown, Grouping::Day));
+        assert_eq!(
+            out,
+            "# Feed items\n\
+             \n## 2025-01-03\n\n- [Third](<https://example.com/Third>) — BBC, 09:30\n\
+             \n## 2025-01-02\n\n- [Second \\[beta\\]](<https://example.com/Second-[beta]>) — Acme, 09:30\n\
+             \n## 2025-01-01\n\n- [First](<https://example.com/First>) — BBC, 09:30\n\
+             \n## No date\n\n- [Undated](<https://example.com/Undated>) — Acme\n"
+        );
+    }
+
+    #[test]
+    fn json_grouped_by_source() {
+        let out = render(&items(), &options(Format::Json, Grouping::Source));
+        let groups: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let summary: Vec<(String, Vec<String>)> = groups
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|g| {
+                let titles = g["items"].as_array().unwrap().iter().map(|i| i["title"].as_str().unwrap().into());
+                (g["group"].as_str().unwrap().into(), titles.collect())
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Acme".into(), vec!["Second [beta]".into(), "Undated".into()]),
+                ("BBC".into(), vec!["Third".into(), "First".into()]),
+            ]
+        );
+
+        let flat: Vec<FeedItem> = serde_json::from_str(&render(&items(), &options(Format::Json, Grouping::None))).unwrap();
+        assert_eq!(flat, items());
+    }
+
+    #[test]
+    fn csv_quotes_fields_and_html_escapes() {
+        let mut list = items();
+        list[0].title = "Quote \"this\", please".into();
+        list[1].title = "<script>".into();
+
+        let csv = render(&list, &options(Format::Csv, Grouping::None));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "published,source,title,link,author,id");
+        assert_eq!(
+            lines[1],
+            "2025-01-03T09:30:00+00:00,BBC,\"Quote \"\"this\"\", please\",https://example.com/Third,,Third"
+        );
+        assert_eq!(lines.len(), 5);
+
+        let html = render(&list, &options(Format::Html, Grouping::Source));
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>Acme</h2>"));
+        assert!(html.contains("&lt;script&gt;</a> <span class=\"meta\">2025-01-02 09:30</span>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn html_links_only_web_urls() {
+        let mut list = items();
+        list[0].link = Some("javascript:alert(1)".into());
+        list[1].link = Some(" JavaScript:alert(2)".into());
+        let html = render(&list, &options(Format::Html, Grouping::None));
+        assert!(!html.to_lowercase().contains("href=\"javascript") && !html.contains("href=\" "));
+        assert!(html.contains("Third (javascript:alert(1))"));
+        assert!(html.contains("<a href=\"https://example.com/First\">First</a>"));
+
+        list[2].link = Some("https://example.com/a>b\n# c".into());
+        let md = render(&list, &options(Format::Markdown, Grouping::None));
+        assert!(md.contains("- Third (javascript:alert(1)) — "), "{md}");
+        assert!(!md.to_lowercase().contains("(<javascript") && !md.contains("(< "), "{md}");
+        assert!(md.contains("[First](<https://example.com/a%3Eb#%20c>)"), "{md}");
+    }
+
+    #[test]
+    fn write_new_does_not_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.md");
+        let options = options(Format::Markdown, Grouping::None);
+        assert_eq!(write_new(&path, &items(), &options).unwrap(), 4);
+        fs::write(&path, "keep").unwrap();
+        assert!(write_new(&path, &items(), &options).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep");
+    }
+
+    #[test]
+    fn date_range_is_inclusive_and_drops_undated() {
+        let range = DateRange {
+            since: NaiveDate::from_ymd_opt(2025, 1, 2),
+            until: NaiveDate::from_ymd_opt(2025, 1, 3),
+        };
+        let options = Options {
+            range,
+            ..options(Format::Markdown, Grouping::None)
+        };
+        let out = render(&items(), &options);
+        assert!(out.starts_with("# Feed items, 2025-01-02 to 2025-01-03\n"));
+        assert!(out.contains("Third") && out.contains("Second"));
+        assert!(!out.contains("First") && !out.contains("Undated"));
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(write(&dir.path().join("out.md"), &items(), &options).unwrap(), 2);
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(Format::from_path(Path::new("digest.MD")), Some(Format::Markdown));
+        assert_eq!(Format::from_path(Path::new("a/b.htm")), Some(Format::Html));
+        assert_eq!(Format::from_path(Path::new("digest.txt")), None);
+        assert_eq!(Format::from_path(Path::new("digest")), None);
+    }
+}
@@ -0,0 +1,435 @@
+//! Filter rules: hide, highlight, pin, mute or notify about items by keyword
+//! or regex.
+//!
+//! Rules come from `[[rule]]` tables in the config file and can be added
+//! and deleted at runtime from the rules view.  [`Rules::apply`] decides
+//! what happens to one item; [`crate::app::App`] applies the verdict as
+//! items are merged, and again to every item when the rules change.
+//!
+//! ```toml
+//! [[rule]]
+//! keyword = "football"
+//! action = "hide"
+//!
+//! [[rule]]
+//! regex = "\\bAcme(Cloud)?\\b"
+//! field = "title"
+//! action = "highlight"
+//! color = "green"
+//! ```
+//!
+//! ## For contributors
+//!
+//! * Matching is case-insensitive for both keywords and regexes, like
+//!   [`crate::search`].  Keywords compile to an escaped regex so there is
+//!   one matching path.
+//! * A rule also has a one-line text form (see [`Rule`]'s `FromStr` and
+//!   `Display`), used by the "add rule" prompt and the rules view.
+//! * To add an action, add a variant to [`Action`], handle it in
+//!   [`Rules::apply`], and decide what the app does with it.
+//! * [`Filter`]s are rules without an action, for outputs that pick items
+//!   (e.g. webhooks).  Both share [`compile`] and [`is_match`].
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use ratatui::style::Color;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::notify::NotifyWith;
+use crate::source::FeedItem;
+
+/// One `[[rule]]` table.  Exactly one of `keyword` and `regex` is set.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Rule {
+    /// Text to look for, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+
+    /// Regular expression to look for, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+
+    /// Which part of the item to look in.
+    #[serde(default)]
+    pub field: Field,
+
+    /// What to do with matching items.
+    pub action: Action,
+
+    /// Colour for [`Action::Highlight`]: a name such as `"green"` or
+    /// `"#ff8800"` [default: yellow].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// How [`Action::Notify`] notifies, where it differs from `[notify]`.
+    #[serde(default, skip_serializing_if = "NotifyWith::is_empty")]
+    pub notify_with: NotifyWith,
+}
+
+/// The part of an item a [`Rule`] looks at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    /// Title, description, source name or link.
+    #[default]
+    Any,
+    Title,
+    /// The description as plain text, without HTML markup.
+    Description,
+    /// [`FeedItem::source_name`].
+    Source,
+    Link,
+}
+
+/// What a [`Rule`] does to matching items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Leave the item out of the list.
+    Hide,
+    /// Draw the item's title in a colour.
+    Highlight,
+    /// Keep the item at the top of the list.
+    Pin,
+    /// Count the item as read while the rule is in place, and draw it
+    /// dimmed.  The read state itself is left alone.
+    Mute,
+    /// Send a notification when the item arrives; see [`crate::notify`].
+    Notify,
+}
+
+/// The combined effect of every rule matching an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Verdict {
+    pub hide: bool,
+    pub pin: bool,
+    pub mute: bool,
+    /// Index of the first matching notify rule.
+    pub notify: Option<usize>,
+    /// Colour of the first matching highlight rule.
+    pub color: Option<Color>,
+}
+
+/// A list of rules, compiled.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+    matchers: Vec<Regex>,
+    colors: Vec<Option<Color>>,
+}
+
+impl Rules {
+    /// Compile `rules`, failing on the first invalid one.
+    pub fn new(rules: Vec<Rule>) -> Result<Self> {
+        let mut matchers = Vec::new();
+        let mut colors = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            let compiled = rule.compile().with_context(|| format!("rule {} ({rule})", i + 1))?;
+            matchers.push(compiled.0);
+            colors.push(compiled.1);
+        }
+        Ok(Self {
+            rules,
+            matchers,
+            colors,
+        })
+    }
+
+    /// The rules, in order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// What the rules say about `item`.
+    pub fn apply(&self, item: &FeedItem) -> Verdict {
+        let mut verdict = Verdict::default();
+        // Only strip the description's markup if some rule needs it.
+        let mut description = None;
+        for (i, ((rule, matcher), color)) in self.rules.iter().zip(&self.matchers).zip(&self.colors).enumerate() {
+            if !is_match(matcher, rule.field, item, &mut description) {
+                continue;
+            }
+            match rule.action {
+                Action::Hide => verdict.hide = true,
+                Action::Pin => verdict.pin = true,
+                Action::Mute => verdict.mute = true,
+                Action::Notify => verdict.notify = verdict.notify.or(Some(i)),
+                Action::Highlight => {
+                    verdict.color = verdict.color.or(*color);
+                }
+            }
+        }
+        verdict
+    }
+}
+
+impl Rule {
+    /// The matcher and highlight colour for this rule.
+    fn compile(&self) -> Result<(Regex, Option<Color>)> {
+        let matcher = compile(&self.keyword, &self.regex)?;
+        let color = match (self.action, &self.color) {
+            (Action::Highlight, Some(name)) => {
+                Some(Color::from_str(name).map_err(|_| anyhow::anyhow!("unknown colour {name:?}"))?)
+            }
+            (Action::Highlight, None) => Some(Color::Yellow),
+            (_, Some(_)) => bail!("only highlight rules take a colour"),
+            (_, None) => None,
+        };
+        if self.action != Action::Notify && !self.notify_with.is_empty() {
+            bail!("only notify rules take `notify_with`");
+        }
+        Ok((matcher, color))
+    }
+}
+
+/// A keyword or regex to look for, without an action: used where items are
+/// picked rather than changed, such as for webhooks.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Filter {
+    /// Text to look for, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+
+    /// Regular expression to look for, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+
+    /// Which part of the item to look in.
+    #[serde(default)]
+    pub field: Field,
+}
+
+/// A list of filters, compiled.
+#[derive(Debug, Clone, Default)]
+pub struct Filters {
+    matchers: Vec<(Regex, Field)>,
+}
+
+impl Filters {
+    /// Compile `filters`, failing on the first invalid one.
+    pub fn new(filters: &[Filter]) -> Result<Self> {
+        let matchers = filters
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let matcher = compile(&f.keyword, &f.regex).with_context(|| format!("filter {}", i + 1))?;
+                Ok((matcher, f.field))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { matchers })
+    }
+
+    /// Whether any filter matches `item`.  With no filters, every item
+    /// matches.
+    pub fn matches(&self, item: &FeedItem) -> bool {
+        let mut description = None;
+        self.matchers.is_empty()
+            || self.matchers.iter().any(|(matcher, field)| is_match(matcher, *field, item, &mut description))
+    }
+}
+
+/// Compile exactly one of `keyword` and `regex` to a case-insensitive
+/// matcher.
+fn compile(keyword: &Option<String>, regex: &Option<String>) -> Result<Regex> {
+    let pattern = match (keyword, regex) {
+        (Some(keyword), None) => regex::escape(keyword),
+        (None, Some(regex)) => regex.clone(),
+        _ => bail!("needs exactly one of `keyword` and `regex`"),
+    };
+    Ok(RegexBuilder::new(&pattern).case_insensitive(true).build()?)
+}
+
+/// Whether `matcher` matches `field` of `item`.  `description` caches the
+/// description as plain text, for the next call on the same item.
+fn is_match(matcher: &Regex, field: Field, item: &FeedItem, description: &mut Option<String>) -> bool {
+    let mut text = |field| -> String {
+        match field {
+            Field::Title => item.title.clone(),
+            Field::Source => item.source_name.clone(),
+            Field::Link => item.link.clone().unwrap_or_default(),
+            _ => description
+                .get_or_insert_with(|| item.description.as_deref().map(crate::html::to_plain).unwrap_or_default())
+                .clone(),
+        }
+    };
+    match field {
+        Field::Any => [Field::Title, Field::Description, Field::Source, Field::Link]
+            .into_iter()
+            .any(|f| matcher.is_match(&text(f))),
+        field => matcher.is_match(&text(field)),
+    }
+}
+
+/// Parses the one-line form `ACTION[:COLOR] [FIELD:]PATTERN`, where a
+/// pattern written `/like this/` is a regex and anything else a keyword:
+///
+/// ```text
+/// hide football
+/// highlight:green title:/\bacme\b/
+/// pin source:Releases
+/// ```
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (action, rest) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .context("expected ACTION PATTERN")?;
+        let (action, color) = match action.split_once(':') {
+            Some((action, color)) => (action, Some(color.to_string())),
+            None => (action, None),
+        };
+        let action = match action {
+            "hide" => Action::Hide,
+            "highlight" => Action::Highlight,
+            "pin" => Action::Pin,
+            "mute" => Action::Mute,
+            "notify" => Action::Notify,
+            other => bail!("unknown action {other:?}: use hide, highlight, pin, mute or notify"),
+        };
+
+        let rest = rest.trim();
+        let (field, pattern) = match rest.split_once(':') {
+            Some(("title", p)) => (Field::Title, p),
+            Some(("description", p)) => (Field::Description, p),
+            Some(("source", p)) => (Field::Source, p),
+            Some(("link", p)) => (Field::Link, p),
+            _ => (Field::Any, rest),
+        };
+        let regex = pattern
+            .strip_prefix('/')
+            .and_then(|p| p.strip_suffix('/'))
+            .map(String::from);
+        if pattern.is_empty() || regex.as_deref() == Some("") {
+            bail!("empty pattern");
+        }
+        Ok(Self {
+            keyword: regex.is_none().then(|| pattern.to_string()),
+            regex,
+            field,
+            action,
+            color,
+            notify_with: NotifyWith::default(),
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.action {
+            Action::Hide => "hide",
+            Action::Highlight => "highlight",
+            Action::Pin => "pin",
+            Action::Mute => "mute",
+            Action::Notify => "notify",
+        };
+        write!(f, "{action}")?;
+        if let Some(color) = &self.color {
+            write!(f, ":{color}")?;
+        }
+        let field = match self.field {
+            Field::Any => "",
+            Field::Title => "title:",
+            Field::Description => "description:",
+            Field::Source => "source:",
+            Field::Link => "link:",
+        };
+        match (&self.keyword, &self.regex) {
+            (_, Some(regex)) => write!(f, " {field}/{regex}/"),
+            (keyword, None) => write!(f, " {field}{}", keyword.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::make_item;
+
+    fn item(title: &str, description: &str, source: &str) -> FeedItem {
+        FeedItem {
+            description: Some(description.into()),
+            link: Some("https://news.example/world/1".into()),
+            source_name: source.into(),
+            ..make_item(title, title, None)
+        }
+    }
+
+    fn rules(lines: &[&str]) -> Rules {
+        Rules::new(lines.iter().map(|l| l.parse().unwrap()).collect()).unwrap()
+    }
+
+    #[test]
+    fn keyword_matches_any_field_ignoring_case() {
+        let rules = rules(&["hide football"]);
+        assert!(rules.apply(&item("FOOTBALL results", "", "BBC")).hide);
+        assert!(rules.apply(&item("Results", "<p>Football</p>", "BBC")).hide);
+        assert!(rules.apply(&item("Results", "", "Football Weekly")).hide);
+        assert!(!rules.apply(&item("Results", "", "BBC")).hide);
+    }
+
+    #[test]
+    fn field_restricts_where_to_look() {
+        let rules = rules(&["pin title:/^acme\\b/", "mute link:/sport/"]);
+        assert!(rules.apply(&item("Acme 2.0 ships", "", "x")).pin);
+        assert!(!rules.apply(&item("New from Acme", "", "x")).pin);
+        assert!(!rules.apply(&item("x", "", "x")).mute, "link is /world/");
+    }
+
+    #[test]
+    fn verdicts_combine_and_first_colour_wins() {
+        let rules = rules(&["highlight:green acme", "highlight:red acme", "pin acme"]);
+        let verdict = rules.apply(&item("Acme", "", "x"));
+        assert_eq!(
+            verdict,
+            Verdict {
+                pin: true,
+                color: Some(Color::Green),
+                ..Verdict::default()
+            }
+        );
+        assert_eq!(rules.apply(&item("Other", "", "x")), Verdict::default());
+    }
+
+    #[test]
+    fn text_form_round_trips() {
+        for line in ["hide football", "highlight:#ff8800 title:/\\bacme\\b/", "pin source:Releases", "mute celebrity gossip", "notify title:rust"] {
+            assert_eq!(line.parse::<Rule>().unwrap().to_string(), line);
+        }
+        let rule: Rule = "highlight description:acme".parse().unwrap();
+        assert_eq!((rule.field, rule.keyword.as_deref()), (Field::Description, Some("acme")));
+    }
+
+    #[test]
+    fn filters_pick_matching_items() {
+        let filter = |text: &str| toml::from_str::<Filter>(text).unwrap();
+        let filters = Filters::new(&[filter("keyword = \"rust\""), filter("regex = \"^bbc\"\nfield = \"source\"")]).unwrap();
+        assert!(filters.matches(&item("Rust 2.0", "", "x")));
+        assert!(filters.matches(&item("Weather", "", "BBC News")));
+        assert!(!filters.matches(&item("Weather", "", "News at BBC")));
+        assert!(Filters::default().matches(&item("anything", "", "x")), "no filters: everything");
+        assert!(Filters::new(&[filter("field = \"title\"")]).is_err(), "no pattern");
+    }
+
+    #[test]
+    fn rejects_bad_rules() {
+        assert!("football".parse::<Rule>().is_err(), "no action");
+        assert!("delete football".parse::<Rule>().is_err());
+        assert!("hide //".parse::<Rule>().is_err());
+        assert!(Rules::new(vec!["hide /(/".parse().unwrap()]).is_err(), "bad regex");
+        assert!(Rules::new(vec!["highlight:octarine x".parse().unwrap()]).is_err());
+        assert!(Rules::new(vec!["hide:red x".parse().unwrap()]).is_err());
+
+        let both: Rule = toml::from_str("keyword = \"a\"\nregex = \"b\"\naction = \"hide\"").unwrap();
+        let err = Rules::new(vec![both]).unwrap_err();
+        assert!(format!("{err:#}").contains("exactly one"));
+    }
+}
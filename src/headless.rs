@@ -0,0 +1,149 @@
+//! `--headless`: print new items to stdout instead of running the TUI.
+//!
+//! The poller, history and filter rules work exactly as in the TUI: poll
+//! messages go through [`App::merge_items`], so items already in the
+//! history (or seen earlier in the run) are not printed again and items a
+//! rule hides are not printed at all.  Each new item becomes one line of
+//! output, from a template or as NDJSON.
+//!
+//! ## For contributors
+//!
+//! * Templates are filled in by [`crate::template`], shared with webhooks.
+//! * Fetch errors go to stderr so that stdout stays machine-readable.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::app::App;
+use crate::poll::PollMsg;
+use crate::sinks::Sinks;
+use crate::source::FeedItem;
+use crate::store::Store;
+
+/// Template used when `--template` is not given.
+pub const DEFAULT_TEMPLATE: &str = "{published} [{source}] {title} {link}";
+
+/// How each item is printed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    /// A line from a template with `{placeholder}`s.
+    Template(String),
+    /// One JSON object per line.
+    Json,
+}
+
+/// Write new items to `out` as they arrive, until a signal sets `signal`,
+/// the poller stops, or `out` is closed.  New items are passed on to
+/// `sinks` as in the TUI.
+pub fn run(
+    app: &mut App,
+    store: &mut Option<Store>,
+    rx: &mpsc::Receiver<PollMsg>,
+    sinks: &mut Sinks,
+    output: &Output,
+    signal: &AtomicUsize,
+    out: &mut impl Write,
+) -> Result<()> {
+    while signal.load(Ordering::SeqCst) == 0 {
+        let msg = match rx.recv_timeout(Duration::from_millis(250)) {
+            Ok(msg) => msg,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                sinks.tick();
+                continue;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        match msg {
+            PollMsg::Items { url, items } => {
+                let mut added = app.merge_polled(&url, items);
+                if let Some(s) = store.as_mut() {
+                    if let Err(e) = s.append(&added) {
+                        eprintln!("livescroll-rss: error saving history: {e:#}");
+                    }
+                }
+                sinks.merged(app, &url, &added);
+                for error in sinks.take_errors() {
+                    eprintln!("livescroll-rss: error {error}");
+                }
+                // Oldest first, so the output reads like a log.
+                added.sort_by(|a, b| b.cmp(a));
+                let shown = added.iter().filter(|item| !app.verdict(item).hide);
+                match print(out, shown, output) {
+                    Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+                    result => result?,
+                }
+            }
+            PollMsg::Status(status) => {
+                if let Some(error) = status.health.last_error.filter(|_| status.health.consecutive_failures > 0) {
+                    eprintln!("livescroll-rss: {}: {error}", status.name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write one line per item and flush, so that readers see items at once.
+fn print<'a>(out: &mut impl Write, items: impl Iterator<Item = &'a FeedItem>, output: &Output) -> io::Result<()> {
+    for item in items {
+        let line = match output {
+            Output::Template(template) => crate::template::fill(template, item),
+            Output::Json => serde_json::to_string(item)?,
+        };
+        writeln!(out, "{line}")?;
+    }
+    out.flush()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::make_item;
+    use chrono::{TimeZone, Utc};
+
+    fn item(id: &str, hour: u32) -> FeedItem {
+        let published = Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap();
+        FeedItem {
+            description: Some("<p>Some <b>bold</b>\n text</p>".into()),
+            ..make_item(id, &format!("Item {id}"), Some(published))
+        }
+    }
+
+    #[test]
+    fn prints_ndjson() {
+        let items = [item("1", 9), item("2", 10)];
+        let mut out = Vec::new();
+        print(&mut out, items.iter(), &Output::Json).unwrap();
+        let lines: Vec<FeedItem> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, items);
+    }
+
+    #[test]
+    fn prints_only_new_visible_items_oldest_first() {
+        let (tx, rx) = mpsc::channel();
+        let mut app = App::new();
+        app.set_rules(crate::filter::Rules::new(vec!["hide title:/item 4/".parse().unwrap()]).unwrap());
+        app.merge_items(vec![item("old", 1)]);
+        let items = vec![item("3", 12), item("old", 1), item("4", 13), item("2", 11)];
+        tx.send(PollMsg::Items { url: "https://example.com/feed".into(), items }).unwrap();
+        drop(tx);
+
+        let mut out = Vec::new();
+        let output = Output::Template("{id}".into());
+        run(&mut app, &mut None, &rx, &mut Sinks::default(), &output, &AtomicUsize::new(0), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "2\n3\n");
+        assert_eq!(app.items.len(), 3, "item 4 is hidden");
+    }
+}
@@ -0,0 +1,123 @@
+//! HTML to styled terminal text.
+//!
+//! Feed descriptions are usually HTML fragments.  [`to_lines`] lays them
+//! out with [`html2text`] — paragraphs, lists, wrapping, and links turned
+//! into numbered footnotes — and maps its markup annotations onto
+//! [`ratatui`] styles for the preview pane in [`crate::ui`].
+//!
+//! ## For contributors
+//!
+//! * Output is already wrapped to the requested width.  The preview pane
+//!   still renders with `Wrap` for its header lines, and takes its line
+//!   count from the wrapped result (see [`crate::ui::preview_line_count`]).
+//! * To style another kind of markup, add an arm to [`style_for`].
+//! * [`to_plain`] strips markup for code that only wants the words, such
+//!   as [`crate::search`].
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use html2text::render::RichAnnotation;
+
+/// Wide enough that [`to_plain`] rarely wraps a paragraph.
+const PLAIN_WIDTH: usize = 10_000;
+
+/// Render `html` as lines no wider than `width` columns.
+///
+/// Markup that cannot be parsed is shown as plain text rather than lost.
+pub fn to_lines(html: &str, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    match html2text::config::rich()
+        .link_footnotes(true)
+        .lines_from_read(html.as_bytes(), width)
+    {
+        Ok(lines) => lines
+            .iter()
+            .map(|line| {
+                Line::from(
+                    line.tagged_strings()
+                        .map(|ts| Span::styled(ts.s.clone(), style_for(&ts.tag)))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect(),
+        Err(_) => html.lines().map(|l| Line::raw(l.to_string())).collect(),
+    }
+}
+
+/// The text of `html` with markup removed and entities decoded, on one
+/// line per block.  For searching, not display.
+pub fn to_plain(html: &str) -> String {
+    html2text::config::plain_no_decorate()
+        .string_from_read(html.as_bytes(), PLAIN_WIDTH)
+        .unwrap_or_else(|_| html.to_string())
+}
+
+/// Combine the styles for a stack of nested annotations (outermost first).
+fn style_for(annotations: &[RichAnnotation]) -> Style {
+    annotations.iter().fold(Style::default(), |style, a| match a {
+        RichAnnotation::Strong => style.add_modifier(Modifier::BOLD),
+        RichAnnotation::Emphasis => style.add_modifier(Modifier::ITALIC),
+        RichAnnotation::Strikeout => style.add_modifier(Modifier::CROSSED_OUT),
+        RichAnnotation::Link(_) => style.fg(Color::Blue).add_modifier(Modifier::UNDERLINED),
+        RichAnnotation::Image(_) => style.fg(Color::DarkGray),
+        RichAnnotation::Code | RichAnnotation::Preformat(_) => style.fg(Color::Yellow),
+        _ => style,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn span<'a>(lines: &'a [Line], content: &str) -> &'a Span<'a> {
+        lines
+            .iter()
+            .flat_map(|l| &l.spans)
+            .find(|s| s.content == content)
+            .unwrap_or_else(|| panic!("no span {content:?}"))
+    }
+
+    #[test]
+    fn lays_out_paragraphs_and_lists() {
+        let lines = to_lines("<p>First</p><p>Second</p><ul><li>one</li><li>two</li></ul>", 40);
+        assert_eq!(text(&lines), ["First", "", "Second", "* one", "* two"]);
+    }
+
+    #[test]
+    fn links_become_footnotes() {
+        let lines = to_lines(r#"<p>See <a href="https://example.com/">this</a>.</p>"#, 40);
+        let text = text(&lines);
+        assert_eq!(text[0], "See this[1].");
+        assert_eq!(text.last().unwrap(), "[1]: https://example.com/");
+        assert!(span(&lines, "this").style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn styles_bold_and_italic() {
+        let lines = to_lines("<b>bold</b> <em>it</em> <strong><i>both</i></strong>", 40);
+        assert_eq!(span(&lines, "bold").style.add_modifier, Modifier::BOLD);
+        assert_eq!(span(&lines, "it").style.add_modifier, Modifier::ITALIC);
+        assert_eq!(span(&lines, "both").style.add_modifier, Modifier::BOLD | Modifier::ITALIC);
+    }
+
+    #[test]
+    fn plain_text_drops_markup() {
+        assert_eq!(to_plain("<p>Fish &amp; <b>chips</b></p>").trim(), "Fish & chips");
+    }
+
+    #[test]
+    fn wraps_to_width() {
+        let lines = to_lines("<p>one two three four five six</p>", 10);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| l.width() <= 10));
+    }
+}
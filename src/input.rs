@@ -1,20 +1,24 @@
 //! Keyboard input handling.
 //!
-//! Maps terminal key events to [`App`] actions.  Adding a new keybinding is
-//! a single match arm in [`handle_key_event`].
+//! Maps terminal key events to [`App`] actions.  Each view has its own
+//! handler, so keys for the item list only act while it is on screen.
 //!
 //! ## For contributors
 //!
 //! To add a new keybinding:
 //!
 //! 1. Add a method on [`App`] for the action (if one doesn't exist).
-//! 2. Add a `KeyCode` match arm in [`handle_key_event`] that calls it.
+//! 2. Add a `KeyCode` match arm that calls it: in `handle_view_key` if it
+//!    works in every view, else in the handler for its view.
 //! 3. Update the help text in [`crate::ui::draw_status_bar`].
 //! 4. Update the keybindings table in `README.md` and the man page.
 
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
-use crate::app::App;
+use crate::app::{App, View};
+
+/// Lines scrolled by PageUp / PageDown in the preview pane.
+const PREVIEW_PAGE: u16 = 10;
 
 /// Process a single key event, updating app state accordingly.
 ///
@@ -24,13 +28,154 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent) {
     if key.kind != KeyEventKind::Press {
         return;
     }
+    if app.searching {
+        handle_search_key(app, key);
+        return;
+    }
+    if app.rule_input.is_some() {
+        handle_rule_input_key(app, key);
+        return;
+    }
+    if app.export_input.is_some() {
+        handle_export_input_key(app, key);
+        return;
+    }
+    match app.view {
+        View::Items => handle_items_key(app, key),
+        View::Sources => handle_view_key(app, key),
+        View::Rules => handle_rules_key(app, key),
+    }
+}
 
+/// Keys in the item list and preview pane.
+fn handle_items_key(app: &mut App, key: KeyEvent) {
     match key.code {
-        KeyCode::Char('q') | KeyCode::Esc => app.quit = true,
         KeyCode::Down | KeyCode::Char('j') => app.select_next(),
         KeyCode::Up | KeyCode::Char('k') => app.select_previous(),
         KeyCode::Home | KeyCode::Char('g') => app.select_first(),
         KeyCode::End | KeyCode::Char('G') => app.select_last(),
+        KeyCode::Char('u') => app.select_next_unread(),
+        KeyCode::Char('m') => app.toggle_read(),
+        KeyCode::Char('M') => app.mark_all_read(),
+        KeyCode::Enter | KeyCode::Char('o') => app.request_open(),
+        KeyCode::Char('J') => app.scroll_preview_down(1),
+        KeyCode::Char('K') => app.scroll_preview_up(1),
+        KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_preview_down(PREVIEW_PAGE),
+        KeyCode::PageUp => app.scroll_preview_up(PREVIEW_PAGE),
+        KeyCode::Char('v') => app.toggle_preview(),
+        KeyCode::Char('f') => app.toggle_follow(),
+        KeyCode::Char('R') => app.refresh_selected(),
+        KeyCode::Char('/') => app.start_search(),
+        KeyCode::Char('n') => app.search_next(),
+        KeyCode::Char('N') => app.search_previous(),
+        KeyCode::Char('E') => app.start_export(),
+        _ => handle_view_key(app, key),
+    }
+}
+
+/// Keys in the rules view.
+fn handle_rules_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Down | KeyCode::Char('j') => app.select_next_rule(),
+        KeyCode::Up | KeyCode::Char('k') => app.select_previous_rule(),
+        KeyCode::Char('a') => app.start_rule_input(),
+        KeyCode::Char('d') | KeyCode::Delete => app.delete_selected_rule(),
+        _ => handle_view_key(app, key),
+    }
+}
+
+/// Keys that work in every view: quitting, switching views and
+/// controlling the poller.  This is all the source dashboard takes.
+fn handle_view_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => app.quit = true,
+        // Raw mode turns Ctrl-C into a key press instead of SIGINT.
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => app.quit = true,
+        KeyCode::Char('s') => app.toggle_sources_view(),
+        KeyCode::Char('F') => app.toggle_rules_view(),
+        KeyCode::Char('r') => app.refresh_all(),
+        KeyCode::Char('p') => app.toggle_pause(),
         _ => {}
     }
 }
+
+/// Keys while the "export to" prompt is open.
+fn handle_export_input_key(app: &mut App, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Enter => app.confirm_export(),
+        KeyCode::Esc => app.cancel_export(),
+        KeyCode::Char('c') if ctrl => app.cancel_export(),
+        KeyCode::Backspace => app.export_input_backspace(),
+        KeyCode::Char(c) if !ctrl => app.export_input_char(c),
+        _ => {}
+    }
+}
+
+/// Keys while the "add rule" prompt is open.
+fn handle_rule_input_key(app: &mut App, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Enter => app.confirm_rule_input(),
+        KeyCode::Esc => app.cancel_rule_input(),
+        KeyCode::Char('c') if ctrl => app.cancel_rule_input(),
+        KeyCode::Backspace => app.rule_input_backspace(),
+        KeyCode::Char(c) if !ctrl => app.rule_input_char(c),
+        _ => {}
+    }
+}
+
+/// Keys while the search prompt is open: typing edits the query.
+fn handle_search_key(app: &mut App, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Enter => app.confirm_search(),
+        KeyCode::Esc => app.cancel_search(),
+        KeyCode::Char('c') if ctrl => app.cancel_search(),
+        KeyCode::Char('r') if ctrl => app.toggle_search_regex(),
+        KeyCode::Backspace => app.search_backspace(),
+        KeyCode::Char(c) if !ctrl => app.search_input(c),
+        _ => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::FeedItem;
+    use crate::testutil::make_item;
+
+    fn press(app: &mut App, code: KeyCode) {
+        handle_key_event(app, KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn item_keys_do_nothing_in_other_views() {
+        let mut app = App::new();
+        app.merge_items(vec![FeedItem {
+            link: Some("https://example.com/1".into()),
+            ..make_item("1", "Item", None)
+        }]);
+        app.list_state.select(Some(0));
+
+        for view in ['s', 'F'] {
+            press(&mut app, KeyCode::Char(view));
+            assert_ne!(app.view, View::Items);
+            for code in [KeyCode::Char('m'), KeyCode::Char('M'), KeyCode::Enter, KeyCode::Char('o')] {
+                press(&mut app, code);
+            }
+            assert_eq!(app.unread_count(), 1, "in {:?}", app.view);
+            assert!(app.take_read_changes().is_empty());
+            assert_eq!(app.take_open_request(), None);
+            press(&mut app, KeyCode::Char(view));
+            assert_eq!(app.view, View::Items);
+        }
+
+        press(&mut app, KeyCode::Char('m'));
+        assert_eq!(app.unread_count(), 0, "the same keys work in the item list");
+    }
+}
@@ -14,102 +14,477 @@
 //!                         └──────────┘
 //! ```
 //!
+//! * **`cli`** / **`config`** — command-line flags and the TOML config file
+//!   listing the feeds to poll.
 //! * **`source/`** — the `DataSource` trait and concrete implementations
-//!   (currently RSS only).
-//! * **`poll`** — spawns a background thread that fetches sources on a timer.
+//!   (RSS 2.0, RSS 1.0/RDF, Atom 1.0 and JSON Feed).
+//! * **`poll`** — spawns a background thread that fetches sources on a timer,
+//!   several at once.
 //! * **`app`** — owns all application state (items, scroll position, etc.).
+//! * **`store`** — append-only history file so items survive restarts.
+//! * **`opener`** — launches item links in the user's browser.
 //! * **`ui`** — pure rendering: reads `App` state and draws widgets.
+//! * **`html`** — converts HTML descriptions to styled text for the preview.
 //! * **`input`** — maps key events to `App` mutations.
+//! * **`search`** — matches items against the `/` search query.
+//! * **`filter`** — rules that hide, highlight, pin, mute or notify about
+//!   items.
+//! * **`headless`** — `--headless`: prints new items instead of drawing.
+//! * **`template`** — `{placeholder}` templates for headless output and
+//!   webhooks.
+//! * **`export`** — writes items to Markdown, CSV, JSON or HTML files.
+//! * **`serve`** — re-publishes the item list over HTTP as RSS, Atom and
+//!   JSON Feed.
+//! * **`webhook`** — POSTs new items to URLs as JSON.
+//! * **`notify`** — bell, terminal and command notifications for new items.
+//! * **`daemon`** — `daemon` / `attach`: polling in the background, the TUI
+//!   as a client over a Unix socket.
+//! * **`sinks`** — passes new items on to the feed server, webhooks and
+//!   notifications.
 //! * **`main`** — wires everything together: parse args, set up the terminal,
 //!   and run the event loop.
 
 mod app;
+mod cli;
+mod config;
+mod daemon;
+mod export;
+mod filter;
+mod headless;
+mod html;
 mod input;
+mod notify;
+mod opener;
+mod opml;
 mod poll;
+mod search;
+mod serve;
+mod sinks;
 mod source;
+mod store;
+mod template;
+#[cfg(test)]
+mod testutil;
 mod ui;
+mod webhook;
 
 use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::Parser;
 use crossterm::{
+    cursor,
     event::{self, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
+use signal_hook::consts::{SIGINT, SIGTERM};
 
 use app::App;
+use cli::Cli;
+use config::Config;
 use poll::PollMsg;
-use source::{DataSource, RssSource};
+use source::HttpClient;
+use store::Store;
 
-fn main() -> Result<()> {
-    // -- parse arguments -----------------------------------------------------
-    let url = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "https://feeds.bbci.co.uk/news/rss.xml".into());
+fn main() -> Result<ExitCode> {
+    // -- parse arguments and config file -------------------------------------
+    let cli = Cli::parse();
+    if let Some(file) = &cli.import_opml {
+        import_opml(file, &cli)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut config = Config::load(cli.config.as_deref())?;
+
+    cli.apply(&mut config);
+
+    if cli.export_opml {
+        print!("{}", opml::write(&config.feeds));
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(cli::Command::Export(args)) = &cli.command {
+        export_history(args, &config)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    cli::use_default_feed(&mut config);
 
     // -- configure data sources ----------------------------------------------
-    // To add more feeds, push additional sources here.
-    let sources: Vec<Box<dyn DataSource>> = vec![
-        Box::new(RssSource::new(&url, "RSS")),
-    ];
+    let feeds: Vec<poll::Feed> = config
+        .feeds
+        .iter()
+        .map(|feed| poll::Feed {
+            source: source::from_config(feed),
+            interval: config.poll_interval(feed),
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.follow_newest = config.follow_newest;
+    app.export_dir = config.export.dir.clone();
+    app.set_rules(filter::Rules::new(config.rules.clone()).context("in the config file")?);
+
+    // -- attach to a daemon, or become one -----------------------------------
+    if let Some(cli::Command::Attach(_)) = &cli.command {
+        let mut client = daemon::Client::connect(&socket_path(&config)?)?;
+        client.notifier = Some(notify::Notifier::new(&config, notify::Delivery::Terminal));
+        let signal = register_signals()?;
+        run_tui(&mut app, &mut Backend::Daemon(&mut client), &config, &signal)?;
+        return Ok(exit_code(&signal));
+    }
+    let listener = match &cli.command {
+        Some(cli::Command::Daemon(_)) => Some(daemon::Listener::bind(&socket_path(&config)?)?),
+        _ => None,
+    };
+
+    // -- start the feed server and webhooks ----------------------------------
+    // A daemon's terminal is not the user's: attached clients ring theirs.
+    let delivery = match listener {
+        Some(_) => notify::Delivery::Commands,
+        None => notify::Delivery::All,
+    };
+    let mut sinks = sinks::Sinks::start(&config, delivery)?;
+    if let Some(addr) = sinks.server.as_ref().and_then(|s| s.addr()) {
+        app.status = format!("Serving feeds at http://{addr}");
+    }
+
+    // -- load history --------------------------------------------------------
+    let mut store = match open_store(&config) {
+        Ok(Some((store, history))) => {
+            app.merge_items(history.items);
+            app.restore_read(history.read);
+            Some(store)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            app.status = format!("History disabled: {e:#}");
+            if cli.headless || listener.is_some() {
+                eprintln!("livescroll-rss: {}", app.status);
+            }
+            None
+        }
+    };
+
+    sinks.list_changed(&app);
 
     // -- start background polling --------------------------------------------
-    let rx = poll::spawn(sources);
+    // Cache validators are only kept across restarts alongside the history:
+    // a 304 on the first poll must not leave an empty list.
+    let http = match history_path(&config).filter(|_| store.is_some()) {
+        Some(path) => HttpClient::persistent(&path.with_file_name("http-cache.json")),
+        None => HttpClient::new(),
+    };
+    let limits = poll::Limits {
+        parallel: config.max_parallel,
+        per_host: config.max_per_host,
+    };
+    let (control, rx) = poll::spawn(feeds, http, limits);
 
-    // -- terminal setup ------------------------------------------------------
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let signal = register_signals()?;
+    let result = if let Some(listener) = &listener {
+        eprintln!("livescroll-rss: listening on {}", listener.path().display());
+        daemon::serve(&mut app, &mut store, &rx, &control, &mut sinks, listener, &signal)
+    } else if cli.headless {
+        let output = cli.output();
+        headless::run(&mut app, &mut store, &rx, &mut sinks, &output, &signal, &mut io::stdout().lock())
+    } else {
+        let mut backend = Backend::Local {
+            store: &mut store,
+            rx: &rx,
+            control: &control,
+            sinks: &mut sinks,
+        };
+        run_tui(&mut app, &mut backend, &config, &signal)
+    };
+    control.shutdown();
+    result?;
+    Ok(exit_code(&signal))
+}
 
-    let mut app = App::new();
+/// SIGINT / SIGTERM set the returned flag, which ends the event loop, so
+/// the terminal is restored on the way out just as for `q`.  (In raw mode
+/// Ctrl-C is a key press, handled in `input`, rather than a signal.)
+fn register_signals() -> Result<Arc<AtomicUsize>> {
+    let signal = Arc::new(AtomicUsize::new(0));
+    for sig in [SIGINT, SIGTERM] {
+        signal_hook::flag::register_usize(sig, Arc::clone(&signal), sig as usize)?;
+    }
+    Ok(signal)
+}
 
-    // -- main event loop -----------------------------------------------------
-    // Runs at ~10 fps (100 ms tick).  Each iteration:
-    //   1. Drain any messages from the poller.
-    //   2. Render the UI.
-    //   3. Poll for keyboard input (non-blocking, up to tick_rate).
-    let tick_rate = Duration::from_millis(100);
+/// Exit as if killed by the signal that ended the loop, if any, as shells
+/// expect.
+fn exit_code(signal: &AtomicUsize) -> ExitCode {
+    match signal.load(Ordering::SeqCst) {
+        0 => ExitCode::SUCCESS,
+        sig => ExitCode::from(128 + sig as u8),
+    }
+}
 
-    loop {
-        // 1. Process poll messages
+/// The daemon's socket, from `--socket`, the config file or the default.
+fn socket_path(config: &Config) -> Result<PathBuf> {
+    config
+        .daemon
+        .socket_path()
+        .context("no runtime directory on this platform; pass --socket")
+}
+
+/// What the TUI's event loop talks to: the poller, history and outputs in
+/// this process, or a daemon that owns them.
+enum Backend<'a> {
+    Local {
+        store: &'a mut Option<Store>,
+        rx: &'a mpsc::Receiver<PollMsg>,
+        control: &'a poll::Control,
+        sinks: &'a mut sinks::Sinks,
+    },
+    Daemon(&'a mut daemon::Client),
+}
+
+impl Backend<'_> {
+    /// Apply what the poller or daemon sent since the last call.  New
+    /// items are saved and passed on to the outputs here.
+    fn receive(&mut self, app: &mut App) -> Result<()> {
+        let (store, rx, sinks) = match self {
+            Backend::Local { store, rx, sinks, .. } => (store, rx, sinks),
+            Backend::Daemon(client) => return client.receive(app),
+        };
         while let Ok(msg) = rx.try_recv() {
             match msg {
-                PollMsg::Items(items) => {
+                PollMsg::Items { url, items } => {
                     let count = items.len();
-                    app.merge_items(items);
+                    let added = app.merge_polled(&url, items);
                     app.status = format!("Fetched {count} items");
+                    if let Some(s) = store.as_mut() {
+                        if let Err(e) = s.append(&added) {
+                            app.status = format!("Error saving history: {e:#}");
+                        }
+                    }
+                    sinks.merged(app, &url, &added);
                 }
-                PollMsg::Error(e) => {
-                    app.status = format!("Error: {e}");
+                PollMsg::Status(status) => app.update_source(status),
+            }
+        }
+        sinks.tick();
+        if let Some(error) = sinks.take_errors().pop() {
+            app.status = format!("Error {error}");
+        }
+        Ok(())
+    }
+
+    /// Save or send the read-state changes and poller commands the input
+    /// handler queued.
+    fn send(&mut self, app: &mut App) -> Result<()> {
+        match self {
+            Backend::Local { store, control, .. } => {
+                let changes = app.take_read_changes();
+                if let Some(s) = store.as_mut().filter(|_| !changes.is_empty()) {
+                    if let Err(e) = s.set_read(&changes) {
+                        app.status = format!("Error saving history: {e:#}");
+                    }
                 }
+                for command in app.take_poll_commands() {
+                    control.send(command);
+                }
+                Ok(())
             }
+            Backend::Daemon(client) => client.send_changes(app),
         }
+    }
+
+    /// Let the outputs know the rules have changed.
+    fn rules_changed(&mut self, app: &App) -> Result<()> {
+        match self {
+            Backend::Local { sinks, .. } => {
+                sinks.list_changed(app);
+                Ok(())
+            }
+            Backend::Daemon(client) => client.send(&daemon::Request::Rules {
+                rules: app.rules().to_vec(),
+            }),
+        }
+    }
+}
+
+/// Set up the terminal, run the event loop, and restore the terminal
+/// however the loop ends.
+fn run_tui(app: &mut App, backend: &mut Backend, config: &Config, signal: &AtomicUsize) -> Result<()> {
+    install_panic_hook();
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = run(&mut terminal, app, backend, config, signal);
+    restore_terminal()?;
+    result
+}
+
+/// The main event loop.  Returns when the user quits or a signal arrives,
+/// or fails if the daemon goes away.
+///
+/// Runs at ~10 fps (100 ms tick).  Each iteration:
+///   1. Apply anything the poller (or daemon) sent.
+///   2. Render the UI.
+///   3. Poll for keyboard input (non-blocking, up to tick_rate).
+///   4. Save or send any read/unread changes and refresh / pause requests,
+///      launch any link the user asked to open, and save edited filter
+///      rules to the config file.
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    backend: &mut Backend,
+    config: &Config,
+    signal: &AtomicUsize,
+) -> Result<()> {
+    let tick_rate = Duration::from_millis(100);
+    let (open_failed, open_failures) = mpsc::channel();
+
+    loop {
+        // 1. Process poll messages
+        backend.receive(app)?;
 
         // 2. Render
-        terminal.draw(|f| ui::draw(&mut app, f))?;
+        let size = terminal.size()?;
+        if let Some(lines) = ui::preview_line_count(app, Rect::new(0, 0, size.width, size.height)) {
+            app.fit_preview(lines);
+        }
+        terminal.draw(|f| ui::draw(app, f))?;
 
         // 3. Handle input
         if event::poll(tick_rate)? {
             if let Event::Key(key) = event::read()? {
-                input::handle_key_event(&mut app, key);
+                input::handle_key_event(app, key);
+            }
+        }
+
+        // 4. Act on what the input handler queued
+        backend.send(app)?;
+        if let Some(url) = app.take_open_request() {
+            app.status = match opener::open(&url, config.opener.as_deref(), &open_failed) {
+                Ok(()) => format!("Opened {url}"),
+                Err(e) => format!("Error opening link: {e:#}"),
+            };
+        }
+        for failure in open_failures.try_iter() {
+            app.status = format!("Error opening link: {failure}");
+        }
+        if let Some((path, items)) = app.take_export_request() {
+            let options = export::Options {
+                format: export::Format::from_path(&path).unwrap_or(export::Format::Markdown),
+                grouping: config.export.group,
+                range: export::DateRange::default(),
+            };
+            match export::write_new(&path, &items, &options) {
+                Ok(n) => app.status = format!("Exported {n} items to {}", path.display()),
+                Err(e) if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::AlreadyExists) => {
+                    app.reject_export(&path, "file exists; choose another name")
+                }
+                Err(e) => app.status = format!("Error exporting: {e:#}"),
+            }
+        }
+        if let Some(rules) = app.take_rules_change() {
+            backend.rules_changed(app)?;
+            let saved = match &config.path {
+                Some(path) => config::save_rules(path, &rules),
+                None => Err(anyhow::anyhow!("no config file location")),
+            };
+            if let Err(e) = saved {
+                app.status = format!("Error saving rules: {e:#}");
             }
         }
 
-        if app.quit {
-            break;
+        if app.quit || signal.load(Ordering::SeqCst) != 0 {
+            return Ok(());
         }
     }
+}
 
-    // -- teardown ------------------------------------------------------------
+/// Leave raw mode and the alternate screen, and show the cursor again.
+fn restore_terminal() -> io::Result<()> {
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    execute!(io::stdout(), LeaveAlternateScreen, cursor::Show)
+}
+
+/// Restore the terminal before the default panic message is printed, so
+/// that the message is readable and the shell usable.  Only panics on the
+/// main thread do this: the UI keeps running if a poller thread dies.
+fn install_panic_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().name() == Some("main") {
+            let _ = restore_terminal();
+        }
+        default(info);
+    }));
+}
 
+/// `livescroll-rss export`: write the items in the history, minus those a
+/// rule hides, to a file or stdout.
+fn export_history(args: &cli::ExportArgs, config: &Config) -> Result<()> {
+    let mut app = App::new();
+    app.set_rules(filter::Rules::new(config.rules.clone()).context("in the config file")?);
+    let (_, history) = open_store(config)?.context("history is disabled, so there is nothing to export")?;
+    app.merge_items(history.items);
+
+    let options = args.options(config.export.group);
+    match &args.output {
+        Some(path) => {
+            export::write(path, &app.items, &options)?;
+        }
+        None => print!("{}", export::render(&app.items, &options)),
+    }
     Ok(())
 }
+
+/// Append the feeds from an OPML file to the config file (creating it if
+/// needed), skipping URLs that are already configured.
+fn import_opml(file: &std::path::Path, cli: &Cli) -> Result<()> {
+    let path = cli
+        .config
+        .clone()
+        .or_else(config::default_path)
+        .context("no config directory on this platform; pass --config")?;
+    let existing = if path.exists() {
+        Config::load(Some(&path))?
+    } else {
+        Config::default()
+    };
+
+    let text = std::fs::read_to_string(file).with_context(|| format!("reading {}", file.display()))?;
+    let feeds: Vec<_> = opml::parse(&text)
+        .with_context(|| format!("in {}", file.display()))?
+        .into_iter()
+        .filter(|f| !existing.feeds.iter().any(|c| c.url == f.url))
+        .collect();
+
+    if !feeds.is_empty() {
+        config::append_feeds(&path, &feeds)?;
+    }
+    eprintln!("Imported {} new feeds into {}", feeds.len(), path.display());
+    Ok(())
+}
+
+/// Open the history store if enabled, returning it with the replayed history.
+fn open_store(config: &Config) -> Result<Option<(Store, store::History)>> {
+    let Some(path) = history_path(config) else {
+        return Ok(None);
+    };
+    Store::open(&path, config.history.retention()).map(Some)
+}
+
+/// The history file location, or `None` if history is disabled.
+fn history_path(config: &Config) -> Option<PathBuf> {
+    if !config.history.enabled {
+        return None;
+    }
+    config.history.path.clone().or_else(store::default_path)
+}
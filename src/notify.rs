@@ -0,0 +1,442 @@
+//! Notifications for new items: the terminal bell, OSC 9 / OSC 777 desktop
+//! notifications, and an external command such as `notify-send`.
+//!
+//! An item notifies when its feed has `notify = true` or a `notify` rule
+//! matches it, unless a rule hides or mutes it.  The `[notify]` table sets
+//! how notifications are delivered, and `notify_with` on a feed or rule
+//! changes that for its items:
+//!
+//! ```toml
+//! [[feed]]
+//! url = "https://github.com/rust-lang/rust/releases.atom"
+//! notify = true
+//! notify_with = { bell = false }
+//!
+//! [[rule]]
+//! keyword = "outage"
+//! action = "notify"
+//! notify_with = { command = "notify-send -u critical %t %b" }
+//!
+//! [notify]
+//! bell = true
+//! osc = "9"                  # "777", or "none" (default)
+//! command = "notify-send %t %b"
+//! per_minute = 4
+//! ```
+//!
+//! ## For contributors
+//!
+//! * Whatever is waiting goes out as one notification ("50 new items") per
+//!   way of delivering it, so a burst never notifies item by item.  At most
+//!   `per_minute` go out in any minute; items arriving past that wait for
+//!   [`Notifier::tick`].
+//! * Each setting comes from the first matching `notify` rule's
+//!   `notify_with`, else the feed's, else `[notify]`.
+//! * The bell and escape sequences are written to stderr: that is the
+//!   terminal in the TUI, and it keeps `--headless` output clean.
+//! * A daemon has no terminal of its own, so it only runs commands
+//!   ([`Delivery::Commands`]).  It tells attached clients which new items
+//!   notify, and each client rings the bell and sends escape sequences on
+//!   its own terminal ([`Delivery::Terminal`]).
+//! * Commands are split and started by the link opener's code; see
+//!   [`crate::opener::command_line`] and [`crate::opener::spawn`].
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::App;
+use crate::config::Config;
+use crate::opener::command_line;
+use crate::source::FeedItem;
+
+/// The window `per_minute` counts over.
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Titles listed in a notification for several items.
+const MAX_TITLES: usize = 3;
+
+/// Terminal escape sequence used for desktop notifications.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Osc {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    /// `ESC ] 9 ; message ST`: iTerm2, WezTerm, Windows Terminal, kitty
+    /// and others.
+    #[serde(rename = "9")]
+    Osc9,
+    /// `ESC ] 777 ; notify ; heading ; body ST`: rxvt-unicode, foot,
+    /// Ghostty and VTE terminals that carry the patch.
+    #[serde(rename = "777")]
+    Osc777,
+}
+
+/// `notify_with` on a feed or rule: how its notifications differ from
+/// `[notify]`.  Unset settings fall back to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NotifyWith {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bell: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osc: Option<Osc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl NotifyWith {
+    /// Whether nothing is overridden.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Which ways of notifying a [`Notifier`] uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Bell, escape sequences and commands: the TUI and `--headless`.
+    All,
+    /// Commands only: the daemon.
+    Commands,
+    /// Bell and escape sequences only: a client attached to a daemon.
+    Terminal,
+}
+
+/// How one notification is delivered, with every override applied.
+#[derive(Debug, Clone, PartialEq)]
+struct Style {
+    bell: bool,
+    osc: Osc,
+    command: Option<String>,
+}
+
+impl Style {
+    /// Whether there is no way left to deliver it.
+    fn is_silent(&self) -> bool {
+        !self.bell && self.osc == Osc::None && self.command.is_none()
+    }
+}
+
+/// Decides which new items notify, and delivers notifications within the
+/// rate limit.
+pub struct Notifier {
+    /// What `[notify]` says.
+    style: Style,
+    per_minute: usize,
+    /// `notify_with` of the feeds with `notify = true`, by URL.  Labels
+    /// and source names can be shared, so items are looked up by the URL
+    /// they were polled from.
+    sources: HashMap<String, NotifyWith>,
+    /// Where the bell and escape sequences go, unless they are not ours to
+    /// send.
+    terminal: Option<Box<dyn Write + Send>>,
+    /// Whether commands are ours to run.
+    commands: bool,
+    /// When each notification of the last minute went out, oldest first.
+    sent: VecDeque<Instant>,
+    /// Items waiting to be notified, by how, in the order they came.
+    pending: Vec<(Style, Vec<FeedItem>)>,
+    errors: Vec<String>,
+    /// Commands that exited unsuccessfully report here.
+    command_failed: mpsc::Sender<String>,
+    command_failures: mpsc::Receiver<String>,
+}
+
+impl Notifier {
+    /// A notifier for the feeds and `[notify]` table in `config`, using
+    /// the ways of notifying `delivery` allows.
+    pub fn new(config: &Config, delivery: Delivery) -> Self {
+        Self::with_terminal(config, delivery, Box::new(io::stderr()))
+    }
+
+    /// Like [`Notifier::new`], writing to `terminal` instead of stderr.
+    pub fn with_terminal(config: &Config, delivery: Delivery, terminal: Box<dyn Write + Send>) -> Self {
+        let (command_failed, command_failures) = mpsc::channel();
+        Self {
+            style: Style {
+                bell: config.notify.bell,
+                osc: config.notify.osc,
+                command: config.notify.command.clone(),
+            },
+            per_minute: config.notify.per_minute.get(),
+            sources: config
+                .feeds
+                .iter()
+                .filter(|f| f.notify)
+                .map(|f| (f.url.clone(), f.notify_with.clone()))
+                .collect(),
+            terminal: (delivery != Delivery::Commands).then_some(terminal),
+            commands: delivery != Delivery::Terminal,
+            sent: VecDeque::new(),
+            pending: Vec::new(),
+            errors: Vec::new(),
+            command_failed,
+            command_failures,
+        }
+    }
+
+    /// Notify about the items in `added`, just polled from the feed at
+    /// `url`, that ask for it, now or once the rate limit allows.
+    pub fn merged(&mut self, app: &App, url: &str, added: &[FeedItem]) {
+        self.queue(app, url, added);
+        self.tick_at(Instant::now());
+    }
+
+    /// Notify about `items` from the feed at `url`, which the daemon has
+    /// already chosen, now or once the rate limit allows.
+    pub fn chosen(&mut self, app: &App, url: &str, items: &[FeedItem]) {
+        self.queue_chosen(app, url, items);
+        self.tick_at(Instant::now());
+    }
+
+    /// Whether `item`, polled from the feed at `url`, asks to notify: the
+    /// feed has `notify = true` or a `notify` rule matches it, and no rule
+    /// hides or mutes it.
+    pub fn wants(&self, app: &App, url: &str, item: &FeedItem) -> bool {
+        let verdict = app.verdict(item);
+        !verdict.hide && !verdict.mute && (verdict.notify.is_some() || self.sources.contains_key(url))
+    }
+
+    /// Send what is waiting, if the rate limit now allows.  Called on every
+    /// turn of the event loop.
+    pub fn tick(&mut self) {
+        self.tick_at(Instant::now());
+    }
+
+    /// Failures since the last call.
+    pub fn take_errors(&mut self) -> Vec<String> {
+        let failed = self.command_failures.try_iter().map(|e| format!("notifying: {e}"));
+        self.errors.extend(failed);
+        std::mem::take(&mut self.errors)
+    }
+
+    fn queue(&mut self, app: &App, url: &str, added: &[FeedItem]) {
+        let wanted: Vec<FeedItem> = added.iter().filter(|item| self.wants(app, url, item)).cloned().collect();
+        self.queue_chosen(app, url, &wanted);
+    }
+
+    fn queue_chosen(&mut self, app: &App, url: &str, items: &[FeedItem]) {
+        let feed = self.sources.get(url);
+        for item in items {
+            let rule = app.verdict(item).notify.map(|i| &app.rules()[i].notify_with);
+            let terminal = self.terminal.is_some();
+            let style = Style {
+                bell: terminal && pick(rule, feed, |w| &w.bell).unwrap_or(self.style.bell),
+                osc: match terminal {
+                    true => pick(rule, feed, |w| &w.osc).unwrap_or(self.style.osc),
+                    false => Osc::None,
+                },
+                command: pick(rule, feed, |w| &w.command)
+                    .or_else(|| self.style.command.clone())
+                    .filter(|_| self.commands),
+            };
+            // Nothing for this notifier to do, so don't count it against
+            // the rate limit.
+            if style.is_silent() {
+                continue;
+            }
+            match self.pending.iter_mut().find(|(s, _)| *s == style) {
+                Some((_, items)) => items.push(item.clone()),
+                None => self.pending.push((style, vec![item.clone()])),
+            }
+        }
+    }
+
+    fn tick_at(&mut self, now: Instant) {
+        while self.sent.front().is_some_and(|&t| now.duration_since(t) >= MINUTE) {
+            self.sent.pop_front();
+        }
+        while !self.pending.is_empty() && self.sent.len() < self.per_minute {
+            self.sent.push_back(now);
+            let (style, mut items) = self.pending.remove(0);
+            items.sort();
+            let (heading, body) = message(&items);
+            if let Err(e) = self.deliver(&style, &heading, &body) {
+                self.errors.push(format!("notifying: {e:#}"));
+            }
+        }
+    }
+
+    fn deliver(&mut self, style: &Style, heading: &str, body: &str) -> Result<()> {
+        let mut out = String::new();
+        if style.bell {
+            out.push('\x07');
+        }
+        match style.osc {
+            Osc::None => {}
+            Osc::Osc9 => out += &format!("\x1b]9;{}: {}\x1b\\", clean(heading), clean(body)),
+            // `;` separates the heading from the body.
+            Osc::Osc777 => out += &format!("\x1b]777;notify;{};{}\x1b\\", clean(heading).replace(';', ","), clean(body)),
+        }
+        if let Some(terminal) = self.terminal.as_mut().filter(|_| !out.is_empty()) {
+            terminal.write_all(out.as_bytes())?;
+            terminal.flush()?;
+        }
+        if let Some(command) = &style.command {
+            let argv = command_line(command, &[("%t", heading), ("%b", body)]);
+            crate::opener::spawn(&argv, &self.command_failed)?;
+        }
+        Ok(())
+    }
+}
+
+/// The rule's setting if it has one, else the feed's.
+fn pick<T: Clone>(rule: Option<&NotifyWith>, feed: Option<&NotifyWith>, setting: impl Fn(&NotifyWith) -> &Option<T>) -> Option<T> {
+    [rule, feed].into_iter().flatten().find_map(|w| setting(w).clone())
+}
+
+/// Heading and body of the notification for `items`, newest first.
+fn message(items: &[FeedItem]) -> (String, String) {
+    if let [item] = items {
+        return (item.source_name.clone(), item.title.clone());
+    }
+    let mut titles: Vec<&str> = items.iter().take(MAX_TITLES).map(|i| i.title.as_str()).collect();
+    if items.len() > MAX_TITLES {
+        titles.push("…");
+    }
+    (format!("{} new items", items.len()), titles.join(" • "))
+}
+
+/// `text` without control characters, which would end an escape sequence.
+fn clean(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::Rules;
+    use crate::testutil::{make_item, Capture};
+
+    fn notifier_for(toml: &str, delivery: Delivery) -> (Notifier, Capture) {
+        let config = Config::parse(toml).unwrap();
+        let terminal = Capture::default();
+        (Notifier::with_terminal(&config, delivery, Box::new(terminal.clone())), terminal)
+    }
+
+    fn notifier(toml: &str) -> (Notifier, Capture) {
+        notifier_for(toml, Delivery::All)
+    }
+
+    fn item(title: &str, source: &str) -> FeedItem {
+        FeedItem {
+            source_name: source.into(),
+            ..make_item(title, title, None)
+        }
+    }
+
+    const LOUD_FEED: &str = "[[feed]]\nurl = \"https://a.example/\"\nlabel = \"A\"\nnotify = true\n";
+    const A: &str = "https://a.example/";
+    const B: &str = "https://b.example/";
+    const C: &str = "https://c.example/";
+
+    #[test]
+    fn a_burst_is_one_notification() {
+        let (mut notifier, terminal) = notifier(&format!("{LOUD_FEED}[notify]\nosc = \"9\"\n"));
+        let mut app = App::new();
+        let added = app.merge_items((0..50).map(|i| item(&format!("Item {i}"), "A")).collect());
+        notifier.merged(&app, A, &added);
+
+        let text = terminal.text();
+        assert_eq!(text.matches('\x07').count(), 1, "{text:?}");
+        assert!(text.starts_with("\x07\x1b]9;50 new items: Item "), "{text:?}");
+        assert!(text.ends_with(" • …\x1b\\"), "{text:?}");
+    }
+
+    #[test]
+    fn rate_limited_items_wait_their_turn() {
+        let (mut notifier, terminal) =
+            notifier(&format!("{LOUD_FEED}[notify]\nbell = false\nosc = \"777\"\nper_minute = 1\n"));
+        let mut app = App::new();
+        let start = Instant::now();
+
+        let added = app.merge_items(vec![item("First; really", "A")]);
+        notifier.queue(&app, A, &added);
+        notifier.tick_at(start);
+        let added = app.merge_items(vec![item("Second", "A")]);
+        notifier.queue(&app, A, &added);
+        notifier.tick_at(start + Duration::from_secs(30));
+        assert_eq!(terminal.text(), "\x1b]777;notify;A;First; really\x1b\\");
+
+        notifier.tick_at(start + Duration::from_secs(61));
+        assert!(terminal.text().ends_with("\x1b]777;notify;A;Second\x1b\\"));
+    }
+
+    #[test]
+    fn feeds_and_rules_choose_what_notifies() {
+        let (mut notifier, _) = notifier(LOUD_FEED);
+        let mut app = App::new();
+        app.set_rules(Rules::new(["notify title:urgent", "hide spam", "mute source:B"].map(|r| r.parse().unwrap()).to_vec()).unwrap());
+        let added = app.merge_items(vec![item("Anything", "A"), item("Urgent spam", "A")]);
+        notifier.queue(&app, A, &added);
+        let added = app.merge_items(vec![item("Urgent but muted", "B")]);
+        notifier.queue(&app, B, &added);
+        let added = app.merge_items(vec![item("Urgent fix", "C"), item("Routine", "C")]);
+        notifier.queue(&app, C, &added);
+        let mut titles: Vec<&str> = notifier.pending.iter().flat_map(|(_, items)| items).map(|i| i.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, ["Anything", "Urgent fix"]);
+    }
+
+    #[test]
+    fn feeds_and_rules_change_how_items_notify() {
+        let toml = "[[feed]]\nurl = \"https://a.example/\"\nlabel = \"A\"\nnotify = true\nnotify_with = { osc = \"9\" }\n\
+                    [[rule]]\nkeyword = \"urgent\"\naction = \"notify\"\nnotify_with = { bell = false }\n\
+                    [notify]\nosc = \"777\"\n";
+        let (mut notifier, terminal) = notifier(toml);
+        let config = Config::parse(toml).unwrap();
+        let mut app = App::new();
+        app.set_rules(Rules::new(config.rules).unwrap());
+        for (url, title, source) in [(A, "Anything", "A"), (C, "Urgent fix", "C"), (A, "Urgent too", "A")] {
+            let added = app.merge_items(vec![item(title, source)]);
+            notifier.merged(&app, url, &added);
+        }
+
+        assert_eq!(
+            terminal.text(),
+            "\x07\x1b]9;A: Anything\x1b\\\x1b]777;notify;C;Urgent fix\x1b\\\x1b]9;A: Urgent too\x1b\\",
+            "feed: OSC 9; rule: no bell; both: the rule's bell and the feed's OSC"
+        );
+    }
+
+    #[test]
+    fn feeds_on_one_host_notify_apart() {
+        let toml = "[[feed]]\nurl = \"https://example.com/loud\"\nnotify = true\nnotify_with = { osc = \"9\" }\n\
+                    [[feed]]\nurl = \"https://example.com/quiet\"\n";
+        let (mut notifier, terminal) = notifier(toml);
+        let mut app = App::new();
+        let added = app.merge_items(vec![item("Quiet", "Example")]);
+        assert!(!notifier.wants(&app, "https://example.com/quiet", &added[0]));
+        notifier.merged(&app, "https://example.com/quiet", &added);
+        let added = app.merge_items(vec![item("Loud", "Example")]);
+        assert!(notifier.wants(&app, "https://example.com/loud", &added[0]));
+        notifier.merged(&app, "https://example.com/loud", &added);
+        assert_eq!(terminal.text(), "\x07\x1b]9;Example: Loud\x1b\\");
+    }
+
+    #[test]
+    fn commands_only_leaves_the_terminal_alone() {
+        let (mut notifier, terminal) = notifier_for(&format!("{LOUD_FEED}[notify]\nosc = \"9\"\n"), Delivery::Commands);
+        let mut app = App::new();
+        let added = app.merge_items(vec![item("Anything", "A")]);
+        assert!(notifier.wants(&app, A, &added[0]));
+        notifier.merged(&app, A, &added);
+        assert_eq!(terminal.text(), "");
+        assert!(notifier.pending.is_empty() && notifier.sent.is_empty(), "nothing to do, so no slot used");
+    }
+
+    #[test]
+    fn command_gets_heading_and_body() {
+        let message = [("%t", "BBC"), ("%b", "News %t")];
+        assert_eq!(command_line("notify-send -a rss %t %b", &message), ["notify-send", "-a", "rss", "BBC", "News %t"]);
+        assert_eq!(command_line("notify-send", &message), ["notify-send", "BBC", "News %t"]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Opening item links in an external program.
+//!
+//! The command is taken from the `opener` key in the config file, then
+//! `$BROWSER`, then the platform default (`xdg-open`, `open` on macOS).
+//! A `%s` in the command is replaced by the URL; otherwise the URL is
+//! appended as the last argument.
+//!
+//! ## For contributors
+//!
+//! * The child runs detached with all three standard streams set to null,
+//!   so a chatty browser cannot scribble over the TUI.  We never wait for
+//!   it on the UI thread; a helper thread reaps it instead, and reports a
+//!   non-zero exit (e.g. `xdg-open` with no handler) over a channel that
+//!   the caller drains.
+//! * Only `http` and `https` links are opened (see [`web_url`]): a feed
+//!   could otherwise hand the browser a `file:` or `javascript:` link, or
+//!   an option such as `--flag` in place of a URL.
+//! * Commands are split on whitespace only — there is no shell quoting.
+//!   Users who need more can point `opener` at a script.
+
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+
+use anyhow::{bail, Context, Result};
+
+/// Fallback when neither the config file nor `$BROWSER` names a command.
+#[cfg(target_os = "macos")]
+const DEFAULT_OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_OPENER: &str = "xdg-open";
+
+/// Launch `url` with the configured opener, `$BROWSER`, or the platform
+/// default, in that order.  If it later exits unsuccessfully, that is
+/// sent to `failures`.
+pub fn open(url: &str, configured: Option<&str>, failures: &mpsc::Sender<String>) -> Result<()> {
+    let browser = std::env::var("BROWSER").ok();
+    let template = configured
+        .or_else(|| {
+            // $BROWSER may be a colon-separated list; use the first entry.
+            browser.as_deref().and_then(|b| b.split(':').find(|s| !s.trim().is_empty()))
+        })
+        .unwrap_or(DEFAULT_OPENER);
+
+    let argv = command_line(template, &[("%s", url)]);
+    if argv.is_empty() {
+        bail!("opener command is empty");
+    }
+    spawn(&argv, failures)
+}
+
+/// Start `argv` detached, as described above, sending a message to
+/// `failures` if it exits unsuccessfully.  Also used for notification
+/// commands.
+pub fn spawn(argv: &[String], failures: &mpsc::Sender<String>) -> Result<()> {
+    let Some((program, args)) = argv.split_first() else {
+        bail!("empty command");
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("running {program}"))?;
+    let (program, failures) = (program.clone(), failures.clone());
+    std::thread::spawn(move || {
+        let failure = match child.wait() {
+            Ok(status) if status.success() => return,
+            Ok(status) => format!("{program} failed ({status})"),
+            Err(e) => format!("waiting for {program}: {e}"),
+        };
+        let _ = failures.send(failure);
+    });
+    Ok(())
+}
+
+/// `link` parsed, if it is an `http` or `https` URL.  Also used by
+/// [`crate::export`] to decide which links to make clickable.
+pub fn web_url(link: &str) -> Option<reqwest::Url> {
+    reqwest::Url::parse(link).ok().filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Split `template` into an argument vector with each `(placeholder,
+/// value)` of `substitutions` filled in, or every value appended in order
+/// if no placeholder appears.  Also used for notification commands.
+pub fn command_line(template: &str, substitutions: &[(&str, &str)]) -> Vec<String> {
+    let mut argv: Vec<String> = template.split_whitespace().map(String::from).collect();
+    if argv.iter().any(|a| substitutions.iter().any(|(p, _)| a.contains(p))) {
+        for arg in &mut argv {
+            *arg = substitute(arg, substitutions);
+        }
+    } else if !argv.is_empty() {
+        argv.extend(substitutions.iter().map(|(_, value)| value.to_string()));
+    }
+    argv
+}
+
+/// `arg` with its placeholders filled in, in one pass so that a value
+/// containing a placeholder is left as it is.
+fn substitute(arg: &str, substitutions: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    let mut rest = arg;
+    while !rest.is_empty() {
+        match substitutions.iter().find(|(p, _)| rest.starts_with(p)) {
+            Some((placeholder, value)) => {
+                out.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                let c = rest.chars().next().expect("not empty");
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_url_without_placeholder() {
+        assert_eq!(
+            command_line("firefox --new-tab", &[("%s", "https://x/")]),
+            ["firefox", "--new-tab", "https://x/"]
+        );
+    }
+
+    #[test]
+    fn substitutes_placeholder() {
+        assert_eq!(
+            command_line("w3m -o url=%s --flag", &[("%s", "https://x/")]),
+            ["w3m", "-o", "url=https://x/", "--flag"]
+        );
+    }
+
+    #[test]
+    fn empty_command_is_an_error() {
+        assert!(command_line("  ", &[("%s", "https://x/")]).is_empty());
+        assert!(open("https://x/", Some(" "), &mpsc::channel().0).is_err());
+    }
+
+    #[test]
+    fn missing_program_is_an_error() {
+        let err = open("https://x/", Some("/nonexistent/livescroll-opener"), &mpsc::channel().0).unwrap_err();
+        assert!(format!("{err:#}").contains("/nonexistent/livescroll-opener"));
+    }
+
+    #[test]
+    fn unsuccessful_exit_is_reported() {
+        let (tx, failures) = mpsc::channel();
+        open("https://x/", Some("false"), &tx).unwrap();
+        let failure = failures.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        assert!(failure.starts_with("false failed ("), "{failure}");
+
+        open("https://x/", Some("true"), &tx).unwrap();
+        drop(tx);
+        assert!(failures.recv_timeout(std::time::Duration::from_secs(5)).is_err(), "success is not reported");
+    }
+}
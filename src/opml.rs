@@ -0,0 +1,369 @@
+//! OPML import and export of the subscription list.
+//!
+//! OPML is the de-facto interchange format between feed readers: a tree of
+//! `<outline>` elements where leaves carry an `xmlUrl` and inner nodes are
+//! folders.  Folder names are kept as [`FeedConfig::group`], with nested
+//! folders joined by `/`; a `/` or `\` within a name is escaped as `\/` or
+//! `\\`.  The `type` attribute carries the
+//! [`FeedKind`]; other readers write `rss` for every feed, so a missing or
+//! unknown type falls back to the default.
+//!
+//! Both directions are pure string transformations; `main()` does the file
+//! I/O.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::encoding::Decoder;
+use quick_xml::{Reader, XmlVersion};
+
+use crate::config::FeedConfig;
+use crate::source::FeedKind;
+
+/// Parse an OPML document into feed configs, in document order.  A URL
+/// listed more than once, e.g. under two folders, is kept the first time.
+pub fn parse(xml: &str) -> Result<Vec<FeedConfig>> {
+    let mut reader = Reader::from_str(xml);
+    let mut feeds = Vec::new();
+    // One entry per open <outline>: Some(name) for folders, None for feeds
+    // that happen to have children.
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut saw_opml = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.name().as_ref() == b"opml" => saw_opml = true,
+            Event::Start(e) if e.name().as_ref() == b"outline" => {
+                let attrs = OutlineAttrs::read(&e, reader.decoder())?;
+                match attrs.xml_url {
+                    Some(url) => {
+                        feeds.push(feed_config(url, attrs.title, attrs.kind, &folders));
+                        folders.push(None);
+                    }
+                    None => folders.push(attrs.title),
+                }
+            }
+            Event::Empty(e) if e.name().as_ref() == b"outline" => {
+                let attrs = OutlineAttrs::read(&e, reader.decoder())?;
+                if let Some(url) = attrs.xml_url {
+                    feeds.push(feed_config(url, attrs.title, attrs.kind, &folders));
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"outline" => {
+                folders.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !saw_opml {
+        bail!("not an OPML document (no <opml> element)");
+    }
+    let mut seen = HashSet::new();
+    feeds.retain(|f| seen.insert(f.url.clone()));
+    Ok(feeds)
+}
+
+/// Render feed configs as an OPML 2.0 document.
+///
+/// Feeds sharing a [`FeedConfig::group`] are placed in the same folder,
+/// and folders and ungrouped feeds appear in order of first appearance.
+pub fn write(feeds: &[FeedConfig]) -> String {
+    let mut root = Vec::new();
+    for feed in feeds {
+        let path = feed.group.as_deref().map(split_group).unwrap_or_default();
+        let mut level = &mut root;
+        for name in path {
+            let i = match level.iter().position(|n| matches!(n, Node::Folder(f, _) if *f == name)) {
+                Some(i) => i,
+                None => {
+                    level.push(Node::Folder(name, Vec::new()));
+                    level.len() - 1
+                }
+            };
+            let Node::Folder(_, children) = &mut level[i] else { unreachable!() };
+            level = children;
+        }
+        level.push(Node::Feed(feed));
+    }
+
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <opml version=\"2.0\">\n  <head>\n    <title>livescroll-rss subscriptions</title>\n  </head>\n  <body>\n",
+    );
+    write_nodes(&mut out, &root, 0);
+    out + "  </body>\n</opml>\n"
+}
+
+/// One entry of the outline tree [`write`] builds.
+enum Node<'a> {
+    Feed(&'a FeedConfig),
+    Folder(String, Vec<Node<'a>>),
+}
+
+fn write_nodes(out: &mut String, nodes: &[Node], depth: usize) {
+    for node in nodes {
+        match node {
+            Node::Folder(name, children) => {
+                let escaped = escape(name.as_str());
+                *out += &format!(
+                    "{}<outline text=\"{escaped}\" title=\"{escaped}\">\n",
+                    indent(depth)
+                );
+                write_nodes(out, children, depth + 1);
+                *out += &format!("{}</outline>\n", indent(depth));
+            }
+            Node::Feed(feed) => {
+                let label = escape(feed.label());
+                let kind = feed.kind.to_possible_value().expect("no skipped variants");
+                *out += &format!(
+                    "{}<outline type=\"{}\" text=\"{label}\" title=\"{label}\" xmlUrl=\"{}\"/>\n",
+                    indent(depth),
+                    kind.get_name(),
+                    escape(&feed.url),
+                );
+            }
+        }
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth + 2)
+}
+
+/// The folder names in a group, which separates them with `/`.  `\/` and
+/// `\\` stand for a `/` and `\` within a name.
+fn split_group(group: &str) -> Vec<String> {
+    let mut names = vec![String::new()];
+    let mut chars = group.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => names.last_mut().unwrap().extend(chars.next()),
+            '/' => names.push(String::new()),
+            c => names.last_mut().unwrap().push(c),
+        }
+    }
+    names
+}
+
+/// `folders` as a group, escaped so that [`split_group`] gets them back.
+fn join_group<'a>(folders: impl Iterator<Item = &'a str>) -> String {
+    folders
+        .map(|name| name.replace('\\', "\\\\").replace('/', "\\/"))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn feed_config(
+    url: String,
+    title: Option<String>,
+    kind: Option<FeedKind>,
+    folders: &[Option<String>],
+) -> FeedConfig {
+    let group = join_group(folders.iter().flatten().map(String::as_str));
+    FeedConfig {
+        label: title,
+        group: (!group.is_empty()).then_some(group),
+        kind: kind.unwrap_or_default(),
+        ..FeedConfig::new(url)
+    }
+}
+
+/// The `<outline>` attributes we use.
+struct OutlineAttrs {
+    /// `title`, falling back to `text` (which OPML 2.0 makes mandatory).
+    title: Option<String>,
+    xml_url: Option<String>,
+    /// `type`, when it names a [`FeedKind`].
+    kind: Option<FeedKind>,
+}
+
+impl OutlineAttrs {
+    fn read(e: &BytesStart, decoder: Decoder) -> Result<Self> {
+        let (mut text, mut title, mut xml_url, mut kind) = (None, None, None, None);
+        for attr in e.attributes() {
+            let attr = attr?;
+            let value = attr
+                .decoded_and_normalized_value(XmlVersion::Implicit1_0, decoder)?
+                .trim()
+                .to_string();
+            if value.is_empty() {
+                continue;
+            }
+            match attr.key.as_ref() {
+                b"text" => text = Some(value),
+                b"title" => title = Some(value),
+                // Some exporters get the camel case wrong.
+                b"xmlUrl" | b"xmlurl" | b"xmlURL" => xml_url = Some(value),
+                b"type" => kind = FeedKind::from_str(&value, true).ok(),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            title: title.or(text),
+            xml_url,
+            kind,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(url: &str, label: &str, group: Option<&str>) -> FeedConfig {
+        FeedConfig {
+            label: Some(label.into()),
+            group: group.map(String::from),
+            ..FeedConfig::new(url)
+        }
+    }
+
+    #[test]
+    fn parses_nested_folders() {
+        let feeds = parse(
+            r#"<?xml version="1.0"?>
+<opml version="1.0">
+  <head><title>Export</title></head>
+  <body>
+    <outline text="Top" xmlUrl="https://top.example/rss"/>
+    <outline text="News">
+      <outline text="BBC" title="BBC News" type="rss" xmlUrl="https://bbc.example/rss"/>
+      <outline text="Tech">
+        <outline text="LWN" xmlUrl="https://lwn.example/rss"/>
+      </outline>
+    </outline>
+    <outline text="Empty folder"/>
+  </body>
+</opml>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            feeds,
+            vec![
+                feed("https://top.example/rss", "Top", None),
+                feed("https://bbc.example/rss", "BBC News", Some("News")),
+                feed("https://lwn.example/rss", "LWN", Some("News/Tech")),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescapes_attributes() {
+        let feeds = parse(
+            r#"<opml><body><outline text="A &amp; B" xmlUrl="https://x/?a=1&amp;b=2"/></body></opml>"#,
+        )
+        .unwrap();
+        assert_eq!(feeds[0].label.as_deref(), Some("A & B"));
+        assert_eq!(feeds[0].url, "https://x/?a=1&b=2");
+    }
+
+    #[test]
+    fn rejects_non_opml() {
+        assert!(parse("<rss version=\"2.0\"><channel/></rss>").is_err());
+        assert!(parse("<opml><body><outline></body></opml>").is_err());
+    }
+
+    #[test]
+    fn round_trips_groups_and_order() {
+        let feeds = vec![
+            feed("https://a/", "A", None),
+            feed("https://b/", "B & co", Some("News")),
+            feed("https://c/", "C", Some("News/Tech")),
+            feed("https://d/", "D", Some("News")),
+            feed("https://e/?x=1&y=2", "E", Some("Blogs")),
+            feed("https://f/", "F", None),
+        ];
+
+        let xml = write(&feeds);
+        assert_eq!(parse(&xml).unwrap(), feeds);
+    }
+
+    #[test]
+    fn groups_apart_share_one_folder() {
+        let feeds = vec![
+            feed("https://a/", "A", Some("X")),
+            feed("https://b/", "B", None),
+            feed("https://c/", "C", Some("X")),
+        ];
+
+        let xml = write(&feeds);
+        assert_eq!(xml.matches(r#"text="X""#).count(), 1, "{xml}");
+        let urls: Vec<String> = parse(&xml).unwrap().into_iter().map(|f| f.url).collect();
+        assert_eq!(urls, ["https://a/", "https://c/", "https://b/"]);
+    }
+
+    #[test]
+    fn slashes_in_folder_names_are_escaped() {
+        let feeds = parse(
+            r#"<opml><body>
+  <outline text="TV/Film"><outline text="A" xmlUrl="https://a/"/></outline>
+  <outline text="C:\"><outline text="B" xmlUrl="https://b/"/></outline>
+</body></opml>"#,
+        )
+        .unwrap();
+        assert_eq!(feeds[0].group.as_deref(), Some(r"TV\/Film"));
+        assert_eq!(feeds[1].group.as_deref(), Some(r"C:\\"));
+
+        let xml = write(&feeds);
+        assert!(xml.contains(r#"<outline text="TV/Film" title="TV/Film">"#), "{xml}");
+        assert_eq!(parse(&xml).unwrap(), feeds);
+    }
+
+    #[test]
+    fn duplicate_urls_are_imported_once() {
+        let feeds = parse(
+            r#"<opml><body>
+  <outline text="News"><outline text="A" xmlUrl="https://a/"/></outline>
+  <outline text="Tech"><outline text="A again" xmlUrl="https://a/"/></outline>
+  <outline text="B" xmlUrl="https://b/"/>
+</body></opml>"#,
+        )
+        .unwrap();
+        assert_eq!(feeds, vec![feed("https://a/", "A", Some("News")), feed("https://b/", "B", None)]);
+    }
+
+    #[test]
+    fn round_trips_feed_kinds() {
+        let feeds: Vec<_> = [FeedKind::Rss, FeedKind::Atom, FeedKind::Rdf, FeedKind::Json]
+            .into_iter()
+            .map(|kind| FeedConfig {
+                kind,
+                ..feed(&format!("https://{kind:?}/"), "F", None)
+            })
+            .collect();
+
+        let xml = write(&feeds);
+        assert!(xml.contains(r#"type="atom""#));
+        assert_eq!(parse(&xml).unwrap(), feeds);
+    }
+
+    #[test]
+    fn unknown_types_fall_back_to_rss() {
+        let feeds = parse(
+            r#"<opml><body>
+  <outline text="A" type="link" xmlUrl="https://a/"/>
+  <outline text="B" type="ATOM" xmlUrl="https://b/"/>
+</body></opml>"#,
+        )
+        .unwrap();
+        assert_eq!(feeds[0].kind, FeedKind::Rss);
+        assert_eq!(feeds[1].kind, FeedKind::Atom);
+    }
+
+    #[test]
+    fn export_uses_host_for_unlabelled_feeds() {
+        let xml = write(&[FeedConfig::new("https://example.org/feed")]);
+        assert!(xml.contains(r#"text="example.org""#));
+        assert_eq!(parse(&xml).unwrap()[0].label.as_deref(), Some("example.org"));
+    }
+}
@@ -1,56 +1,703 @@
 //! Background feed polling.
 //!
-//! Runs on a dedicated thread, periodically fetching all configured data
-//! sources and sending results to the UI thread over an [`mpsc`] channel.
+//! Runs a scheduler thread that keeps one schedule entry per source and
+//! hands whichever sources are due to a small pool of fetch workers.
+//! Results go to the UI thread over an [`mpsc`] channel.
 //!
 //! ## For contributors
 //!
-//! The poller is intentionally simple: it loops forever, fetches every source
-//! sequentially, sends results, then sleeps.  If you need concurrent fetching
-//! of multiple sources, consider spawning one thread per source or switching
-//! to async with [`tokio`].
+//! * Each source is polled on its own interval ([`Feed::interval`], from the
+//!   config file), stretched to the feed's own `<ttl>` / `sy:` update
+//!   interval if that is longer, and pushed out of its `<skipHours>` /
+//!   `<skipDays>` — see [`next_due`] and [`FeedHints`].
+//! * A failing source is retried with exponential backoff plus jitter (see
+//!   [`backoff`]), or later if a 429 / 503 response said `Retry-After`.
+//! * A [`SourceStatus`] for every source is sent when the thread starts and
+//!   again after every fetch attempt, for the status bar and the source
+//!   dashboard.
+//! * The UI steers the thread through a [`Control`]: refresh now, pause and
+//!   resume, and shut down.  Commands and finished fetches arrive on one
+//!   channel, so the scheduler has a single place to wait and any command
+//!   wakes it at once.
+//! * Up to [`Limits::parallel`] fetches run at once, at most
+//!   [`Limits::per_host`] of them against any one host, so a slow server
+//!   only holds up its own feeds.  A source is moved to a worker for the
+//!   fetch and handed back afterwards, so `DataSource` need not be `Sync`.
 
-use std::sync::mpsc;
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
-use crate::source::{DataSource, FeedItem};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::source::{DataSource, FeedHints, FeedItem, Fetched, HttpClient, HttpError};
+
+/// Longest wait before retrying a failing source, unless its normal
+/// interval is longer still.
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
 
 /// Messages sent from the poller thread to the UI thread.
 pub enum PollMsg {
-    /// A successful fetch returned these items.
-    Items(Vec<FeedItem>),
-    /// A fetch failed with this error description.
-    Error(String),
+    /// A successful fetch of the source at `url` returned these items.
+    Items { url: String, items: Vec<FeedItem> },
+    /// The state of one source, sent at startup and after every fetch
+    /// attempt.
+    Status(SourceStatus),
+}
+
+/// Everything the poller knows about one source.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct SourceStatus {
+    /// [`DataSource::name`].
+    pub name: String,
+    /// [`DataSource::url`].
+    pub url: String,
+    /// When the last fetch attempt finished.
+    pub last_fetch: Option<DateTime<Utc>>,
+    /// HTTP status of the last response, if there was one.
+    pub http_status: Option<u16>,
+    /// When the source will be fetched next.
+    pub next_poll: Option<DateTime<Utc>>,
+    pub health: Health,
+}
+
+/// How fetching one source has been going.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Health {
+    /// When the source last fetched successfully.
+    pub last_success: Option<DateTime<Utc>>,
+    /// The most recent error, kept after the source recovers.
+    pub last_error: Option<String>,
+    /// Failed attempts since the last success; `0` means healthy.
+    pub consecutive_failures: u32,
+}
+
+/// A source to poll and how often.
+pub struct Feed {
+    pub source: Box<dyn DataSource>,
+    /// Time between fetches, unless the feed asks for longer.
+    pub interval: Duration,
+}
+
+/// How many fetches may run at the same time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Fetches in flight across all sources.
+    pub parallel: usize,
+    /// Fetches in flight against any one host.
+    pub per_host: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            parallel: 8,
+            per_host: 2,
+        }
+    }
+}
+
+/// Requests from the UI to the poller thread, sent through [`Control`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Command {
+    /// Fetch every source now.
+    RefreshAll,
+    /// Fetch the source with this [`DataSource::url`] now.
+    Refresh(String),
+    /// Stop scheduled fetches.  Fetches already running finish, and
+    /// refreshes still happen.
+    Pause,
+    /// Resume scheduled fetches; any that fell due meanwhile run at once.
+    Resume,
 }
 
-/// How often the poller re-fetches all sources.
-const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Sends [`Command`]s to the poller thread, and stops it.
+pub struct Control {
+    events: mpsc::Sender<Event>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl Control {
+    /// Send `command`.  Does nothing if the poller has stopped.
+    pub fn send(&self, command: Command) {
+        let _ = self.events.send(Event::Command(command));
+    }
+
+    /// Stop the poller and wait for its scheduler thread to exit.
+    ///
+    /// Returns promptly: the scheduler is woken from its wait, and fetches
+    /// still running are abandoned — their workers exit once the request
+    /// completes or times out.
+    pub fn shutdown(self) {
+        let _ = self.events.send(Event::Shutdown);
+        let _ = self.thread.join();
+    }
+}
+
+/// What wakes the scheduler, besides a source falling due.
+enum Event {
+    Command(Command),
+    Done(Done),
+    Shutdown,
+}
+
+/// A [`Feed`]'s scheduling state.
+struct Slot {
+    /// The source, or `None` while a worker is fetching it.
+    source: Option<Box<dyn DataSource>>,
+    interval: Duration,
+    /// Host part of the source URL, for [`Limits::per_host`].
+    host: String,
+    /// Hints from the last document downloaded.
+    hints: FeedHints,
+    status: SourceStatus,
+    due: DateTime<Utc>,
+    /// Fetch as soon as the limits allow, whatever `due` says.
+    refresh: bool,
+}
+
+impl Slot {
+    /// Whether the slot should be fetched at `now`.
+    fn wanted(&self, now: DateTime<Utc>, paused: bool) -> bool {
+        self.source.is_some() && (self.refresh || (!paused && self.due <= now))
+    }
+}
+
+/// A fetch handed to a worker.
+struct Job {
+    slot: usize,
+    source: Box<dyn DataSource>,
+}
+
+/// A worker's answer: the source back, and how its fetch went.
+struct Done {
+    slot: usize,
+    source: Box<dyn DataSource>,
+    result: anyhow::Result<Fetched>,
+}
 
 /// Spawn the background polling thread.
 ///
-/// Returns a receiver that the main loop should drain on every tick.
-/// The thread runs until the process exits (there is no explicit shutdown
-/// signal — the channel closes when the receiver is dropped).
-pub fn spawn(sources: Vec<Box<dyn DataSource>>) -> mpsc::Receiver<PollMsg> {
+/// Every feed is fetched once straight away, then on its own schedule, with
+/// at most `limits` fetches in flight.  All sources download through
+/// `http`.  Returns a [`Control`] for steering and stopping the poller, and
+/// a receiver that the main loop should drain on every tick.  The thread
+/// also stops if the receiver is dropped.
+pub fn spawn(feeds: Vec<Feed>, http: HttpClient, limits: Limits) -> (Control, mpsc::Receiver<PollMsg>) {
     let (tx, rx) = mpsc::channel();
+    let (event_tx, events) = mpsc::channel();
+    let control_tx = event_tx.clone();
 
-    thread::spawn(move || {
+    let thread = thread::spawn(move || {
+        let now = Utc::now();
+        let mut slots: Vec<Slot> = feeds
+            .into_iter()
+            .map(|feed| Slot {
+                status: SourceStatus {
+                    name: feed.source.name().to_string(),
+                    url: feed.source.url().to_string(),
+                    next_poll: Some(now),
+                    ..SourceStatus::default()
+                },
+                host: host_of(feed.source.url()),
+                source: Some(feed.source),
+                interval: feed.interval,
+                hints: FeedHints::default(),
+                due: now,
+                refresh: false,
+            })
+            .collect();
+        for slot in &slots {
+            if tx.send(PollMsg::Status(slot.status.clone())).is_err() {
+                return;
+            }
+        }
+
+        let http = Arc::new(http);
+        let (job_tx, job_rx) = mpsc::channel::<Job>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        for _ in 0..limits.parallel.max(1).min(slots.len()) {
+            let (jobs, done, http) = (job_rx.clone(), event_tx.clone(), http.clone());
+            thread::spawn(move || worker(&jobs, &done, &http));
+        }
+        drop(event_tx);
+
+        let mut per_host: HashMap<String, usize> = HashMap::new();
+        let mut in_flight = 0;
+        let mut paused = false;
         loop {
-            for src in &sources {
-                let msg = match src.fetch() {
-                    Ok(items) => PollMsg::Items(items),
-                    Err(e) => PollMsg::Error(format!("{}: {e}", src.name())),
-                };
+            // Start every wanted fetch the limits allow.  Refreshes go
+            // first, then ties go to the earlier feed, so the first round
+            // keeps config order.
+            let now = Utc::now();
+            let mut wanted: Vec<usize> = (0..slots.len()).filter(|&i| slots[i].wanted(now, paused)).collect();
+            wanted.sort_by_key(|&i| (!slots[i].refresh, slots[i].due));
+            for i in wanted {
+                if in_flight >= limits.parallel.max(1) {
+                    break;
+                }
+                let busy = per_host.entry(slots[i].host.clone()).or_default();
+                if *busy >= limits.per_host.max(1) {
+                    continue;
+                }
+                *busy += 1;
+                in_flight += 1;
+                slots[i].refresh = false;
+                let source = slots[i].source.take().expect("idle slot has its source");
+                if job_tx.send(Job { slot: i, source }).is_err() {
+                    return;
+                }
+            }
+
+            // Sleep until the next source is due, a fetch finishes or a
+            // command arrives.  Wanted sources held back by the limits wait
+            // for a fetch to finish.
+            let next = slots
+                .iter()
+                .filter(|s| !paused && s.source.is_some() && s.due > now)
+                .map(|s| s.due)
+                .min();
+            let event = match next {
+                Some(due) => match events.recv_timeout((due - now).to_std().unwrap_or_default()) {
+                    Ok(event) => event,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                },
+                None => match events.recv() {
+                    Ok(event) => event,
+                    Err(_) => return,
+                },
+            };
+
+            let done = match event {
+                Event::Done(done) => done,
+                Event::Shutdown => return,
+                Event::Command(Command::Pause) => {
+                    paused = true;
+                    continue;
+                }
+                Event::Command(Command::Resume) => {
+                    paused = false;
+                    continue;
+                }
+                Event::Command(command) => {
+                    for slot in &mut slots {
+                        slot.refresh |= match &command {
+                            Command::Refresh(url) => slot.status.url == *url,
+                            _ => true,
+                        };
+                    }
+                    continue;
+                }
+            };
+
+            let slot = &mut slots[done.slot];
+            slot.source = Some(done.source);
+            in_flight -= 1;
+            if let Some(busy) = per_host.get_mut(&slot.host) {
+                *busy -= 1;
+            }
+            let items = record(slot, done.result, &http);
+            let msgs = items
+                .map(|items| PollMsg::Items {
+                    url: slot.status.url.clone(),
+                    items,
+                })
+                .into_iter()
+                .chain([PollMsg::Status(slot.status.clone())]);
+            for msg in msgs {
                 // If the receiver is gone the main thread has exited;
                 // silently stop polling.
                 if tx.send(msg).is_err() {
                     return;
                 }
             }
-            thread::sleep(POLL_INTERVAL);
         }
     });
 
-    rx
+    (
+        Control {
+            events: control_tx,
+            thread,
+        },
+        rx,
+    )
+}
+
+/// Fetch jobs until the scheduler goes away.
+fn worker(jobs: &Mutex<mpsc::Receiver<Job>>, done: &mpsc::Sender<Event>, http: &HttpClient) {
+    loop {
+        // Hold the lock only while waiting, not while fetching.
+        let job = jobs.lock().unwrap().recv();
+        let Ok(Job { slot, source }) = job else { return };
+        let result = source.fetch(http);
+        if done.send(Event::Done(Done { slot, source, result })).is_err() {
+            return;
+        }
+    }
+}
+
+/// Update `slot`'s health and schedule from a finished fetch.  Returns the
+/// items to pass on, if it succeeded.
+fn record(slot: &mut Slot, result: anyhow::Result<Fetched>, http: &HttpClient) -> Option<Vec<FeedItem>> {
+    let now = Utc::now();
+    let health = &mut slot.status.health;
+    let items = match result {
+        Ok(fetched) => {
+            if let Some(hints) = fetched.hints {
+                slot.hints = hints;
+            }
+            health.last_success = Some(now);
+            health.consecutive_failures = 0;
+            slot.due = next_due(now, slot.interval, &slot.hints);
+            Some(fetched.items)
+        }
+        Err(e) => {
+            health.last_error = Some(format!("{e:#}"));
+            health.consecutive_failures += 1;
+            let jitter = 0.8 + 0.4 * fastrand::f64();
+            let mut wait = backoff(slot.interval, health.consecutive_failures, jitter);
+            if let Some(retry_after) = e.downcast_ref::<HttpError>().and_then(|h| h.retry_after) {
+                wait = wait.max(retry_after);
+            }
+            let wait = TimeDelta::from_std(wait).unwrap_or(TimeDelta::days(365));
+            slot.due = slot.hints.next_allowed(now + wait);
+            None
+        }
+    };
+
+    slot.status.last_fetch = Some(now);
+    slot.status.http_status = http.last_status(&slot.status.url).map(|s| s.as_u16());
+    slot.status.next_poll = Some(slot.due);
+    items
+}
+
+/// The host a source URL points at, or the whole URL if it has none.
+fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// How long to wait before retrying a source that has failed `failures`
+/// times in a row: the interval doubled per failure, scaled by `jitter` so
+/// that sources failing together don't retry together, and capped at
+/// [`MAX_BACKOFF`] (or the interval, if longer).
+fn backoff(interval: Duration, failures: u32, jitter: f64) -> Duration {
+    let cap = MAX_BACKOFF.max(interval);
+    let doubled = interval.saturating_mul(2u32.saturating_pow(failures.min(16)));
+    doubled.mul_f64(jitter).min(cap)
+}
+
+/// When to poll a feed next, having just fetched it at `now`.
+fn next_due(now: DateTime<Utc>, interval: Duration, hints: &FeedHints) -> DateTime<Utc> {
+    let wait = hints.min_interval.map_or(interval, |min| min.max(interval));
+    let wait = TimeDelta::from_std(wait).unwrap_or(TimeDelta::days(365));
+    hints.next_allowed(now + wait)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::make_item;
+    use chrono::{TimeZone, Weekday};
+
+    /// A source that returns one item named after itself per fetch.
+    struct Counter(&'static str);
+
+    impl DataSource for Counter {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn url(&self) -> &str {
+            self.0
+        }
+
+        fn fetch(&self, _: &HttpClient) -> anyhow::Result<Fetched> {
+            Ok(Fetched {
+                items: vec![FeedItem {
+                    source_name: self.0.into(),
+                    ..make_item("", "", None)
+                }],
+                hints: None,
+            })
+        }
+    }
+
+    /// A source that fails a given number of times, then succeeds.
+    struct Flaky(std::sync::atomic::AtomicU32);
+
+    impl DataSource for Flaky {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn url(&self) -> &str {
+            "https://flaky.example/"
+        }
+
+        fn fetch(&self, _: &HttpClient) -> anyhow::Result<Fetched> {
+            use std::sync::atomic::Ordering;
+            let left = self.0.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+            if left.is_ok() {
+                anyhow::bail!("boom");
+            }
+            Ok(Fetched::default())
+        }
+    }
+
+    /// A source that takes a while to fetch and records how many fetches
+    /// were in flight at once.
+    struct Slow {
+        url: &'static str,
+        running: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl DataSource for Slow {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn url(&self) -> &str {
+            self.url
+        }
+
+        fn fetch(&self, _: &HttpClient) -> anyhow::Result<Fetched> {
+            use std::sync::atomic::Ordering;
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(Fetched::default())
+        }
+    }
+
+    /// Fetch each of `urls` once with `limits`; returns the most fetches
+    /// seen in flight at once.
+    fn peak_parallelism(urls: &[&'static str], limits: Limits) -> usize {
+        let running = Arc::default();
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let feeds = urls
+            .iter()
+            .map(|&url| Feed {
+                source: Box::new(Slow {
+                    url,
+                    running: Arc::clone(&running),
+                    peak: Arc::clone(&peak),
+                }),
+                interval: Duration::from_secs(3600),
+            })
+            .collect();
+        let (_, rx) = spawn(feeds, HttpClient::new(), limits);
+
+        let mut fetched = 0;
+        while fetched < urls.len() {
+            if let PollMsg::Status(status) = rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                fetched += usize::from(status.last_fetch.is_some());
+            }
+        }
+        peak.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[test]
+    fn fetches_run_in_parallel_up_to_limit() {
+        let urls = ["https://a/1", "https://b/1", "https://c/1", "https://d/1", "https://e/1"];
+        let limits = Limits {
+            parallel: 3,
+            per_host: 3,
+        };
+        assert_eq!(peak_parallelism(&urls, limits), 3);
+    }
+
+    #[test]
+    fn fetches_per_host_are_limited() {
+        let urls = ["https://a/1", "https://a/2", "https://a/3", "https://b/1"];
+        let limits = Limits {
+            parallel: 8,
+            per_host: 1,
+        };
+        assert_eq!(peak_parallelism(&urls, limits), 2, "one for a, one for b");
+    }
+
+    #[test]
+    fn host_of_falls_back_to_url() {
+        assert_eq!(host_of("https://feeds.example.com:8080/rss"), "feeds.example.com");
+        assert_eq!(host_of("not a url"), "not a url");
+    }
+
+    #[test]
+    fn next_due_uses_longer_of_interval_and_feed_hint() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let minute = Duration::from_secs(60);
+        let mut hints = FeedHints::default();
+        assert_eq!(next_due(now, minute, &hints), now + TimeDelta::minutes(1));
+
+        hints.min_interval = Some(30 * minute);
+        assert_eq!(next_due(now, minute, &hints), now + TimeDelta::minutes(30));
+        assert_eq!(next_due(now, 60 * minute, &hints), now + TimeDelta::hours(1));
+    }
+
+    #[test]
+    fn next_due_respects_skip_days() {
+        // 2025-01-01 is a Wednesday.
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 23, 59, 0).unwrap();
+        let hints = FeedHints {
+            skip_days: vec![Weekday::Thu],
+            ..Default::default()
+        };
+        assert_eq!(
+            next_due(now, Duration::from_secs(120), &hints),
+            Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn sources_are_scheduled_independently() {
+        let (_, rx) = spawn(
+            vec![
+                Feed {
+                    source: Box::new(Counter("fast")),
+                    interval: Duration::from_millis(20),
+                },
+                Feed {
+                    source: Box::new(Counter("slow")),
+                    interval: Duration::from_secs(3600),
+                },
+            ],
+            HttpClient::new(),
+            Limits::default(),
+        );
+
+        let mut counts = (0, 0);
+        while counts.0 + counts.1 < 6 {
+            match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                PollMsg::Items { items, .. } if items[0].source_name == "fast" => counts.0 += 1,
+                PollMsg::Items { .. } => counts.1 += 1,
+                PollMsg::Status(status) => assert_eq!(status.health.consecutive_failures, 0),
+            }
+        }
+        assert_eq!(counts, (5, 1), "slow source is fetched only once");
+    }
+
+    /// Source names of the next `n` batches of items from `rx`.
+    fn next_batches(rx: &mpsc::Receiver<PollMsg>, n: usize) -> Vec<String> {
+        let mut names = Vec::new();
+        while names.len() < n {
+            if let PollMsg::Items { items, .. } = rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                names.push(items[0].source_name.clone());
+            }
+        }
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn refresh_fetches_now() {
+        let hourly = |name| Feed {
+            source: Box::new(Counter(name)),
+            interval: Duration::from_secs(3600),
+        };
+        let (control, rx) = spawn(vec![hourly("a"), hourly("b")], HttpClient::new(), Limits::default());
+        assert_eq!(next_batches(&rx, 2), ["a", "b"]);
+
+        control.send(Command::Refresh("b".into()));
+        assert_eq!(next_batches(&rx, 1), ["b"]);
+        control.send(Command::RefreshAll);
+        assert_eq!(next_batches(&rx, 2), ["a", "b"]);
+    }
+
+    #[test]
+    fn pause_stops_scheduled_fetches_only() {
+        let (control, rx) = spawn(
+            vec![Feed {
+                source: Box::new(Counter("fast")),
+                interval: Duration::from_millis(10),
+            }],
+            HttpClient::new(),
+            Limits::default(),
+        );
+        next_batches(&rx, 1);
+        control.send(Command::Pause);
+        // Let a fetch that was already running finish.
+        thread::sleep(Duration::from_millis(50));
+        while rx.try_recv().is_ok() {}
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err(), "nothing while paused");
+
+        control.send(Command::RefreshAll);
+        assert_eq!(next_batches(&rx, 1), ["fast"], "refresh works while paused");
+        control.send(Command::Resume);
+        assert_eq!(next_batches(&rx, 3).len(), 3);
+    }
+
+    #[test]
+    fn shutdown_interrupts_the_wait() {
+        let (control, rx) = spawn(
+            vec![Feed {
+                source: Box::new(Counter("hourly")),
+                interval: Duration::from_secs(3600),
+            }],
+            HttpClient::new(),
+            Limits::default(),
+        );
+        next_batches(&rx, 1);
+
+        let start = std::time::Instant::now();
+        control.shutdown();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        while let Ok(msg) = rx.recv_timeout(Duration::from_secs(5)) {
+            assert!(matches!(msg, PollMsg::Status(_)), "no fetches after shutdown");
+        }
+        assert_eq!(rx.try_recv().err(), Some(mpsc::TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(backoff(minute, 1, 1.0), 2 * minute);
+        assert_eq!(backoff(minute, 3, 1.0), 8 * minute);
+        assert_eq!(backoff(minute, 10, 1.0), MAX_BACKOFF);
+        assert_eq!(backoff(minute, 100, 1.0), MAX_BACKOFF, "no overflow");
+        assert_eq!(backoff(3 * MAX_BACKOFF, 2, 1.0), 3 * MAX_BACKOFF, "never below interval");
+        assert_eq!(backoff(minute, 1, 0.5), minute);
+        assert_eq!(backoff(minute, 10, 1.2), MAX_BACKOFF, "jitter stays under the cap");
+    }
+
+    #[test]
+    fn failures_are_counted_until_success() {
+        let (_, rx) = spawn(
+            vec![Feed {
+                source: Box::new(Flaky(2.into())),
+                interval: Duration::from_millis(5),
+            }],
+            HttpClient::new(),
+            Limits::default(),
+        );
+
+        let mut statuses = Vec::new();
+        while statuses.len() < 4 {
+            if let PollMsg::Status(status) = rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                assert_eq!((status.name.as_str(), status.url.as_str()), ("flaky", "https://flaky.example/"));
+                statuses.push(status);
+            }
+        }
+        assert!(statuses[0].last_fetch.is_none(), "initial status before any fetch");
+        assert!(statuses[1].last_fetch.is_some());
+        assert!(statuses[1].next_poll > statuses[1].last_fetch);
+        let healths: Vec<_> = statuses[1..].iter().map(|s| s.health.clone()).collect();
+        let failures: Vec<_> = healths.iter().map(|h| h.consecutive_failures).collect();
+        assert_eq!(failures, [1, 2, 0]);
+        assert_eq!(healths[1].last_error.as_deref(), Some("boom"));
+        assert!(healths[1].last_success.is_none());
+        assert!(healths[2].last_success.is_some());
+        assert_eq!(healths[2].last_error.as_deref(), Some("boom"), "last error is kept");
+    }
 }
@@ -0,0 +1,127 @@
+//! Searching items by title and description.
+//!
+//! A [`Search`] is built from what the user typed after `/`.  Matching is
+//! case-insensitive; by default the query is literal text, and in regex
+//! mode it is a [`regex`] pattern.  [`crate::app::App`] re-builds the search
+//! on every keystroke, and [`crate::ui`] uses [`Search::ranges`] to
+//! highlight hits in the item list.
+//!
+//! ## For contributors
+//!
+//! * Both modes compile to a [`Regex`] (literal queries are escaped), so
+//!   there is only one matching path to keep correct.
+//! * Descriptions are matched as plain text, so searching for `p` does not
+//!   hit every `<p>` tag.  The caller converts them once per item (see
+//!   [`crate::html::to_plain`]): converting on every match was too slow
+//!   for long lists.
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+/// A compiled search query.
+#[derive(Debug, Clone)]
+pub struct Search {
+    /// The text typed at the prompt.
+    pub query: String,
+    /// Whether `query` is a regular expression rather than literal text.
+    pub regex: bool,
+    /// `None` if the query is empty or not a valid pattern.
+    matcher: Option<Regex>,
+}
+
+impl Search {
+    /// Compile `query`.  An invalid pattern gives a search that matches
+    /// nothing; see [`Search::error`].
+    pub fn new(query: &str, regex: bool) -> Self {
+        let pattern = if regex { query.to_string() } else { regex::escape(query) };
+        let matcher = (!query.is_empty())
+            .then(|| RegexBuilder::new(&pattern).case_insensitive(true).build().ok())
+            .flatten();
+        Self {
+            query: query.to_string(),
+            regex,
+            matcher,
+        }
+    }
+
+    /// Why the query can't be used, if it is an invalid pattern.
+    pub fn error(&self) -> Option<String> {
+        if self.query.is_empty() || self.matcher.is_some() {
+            return None;
+        }
+        RegexBuilder::new(&self.query)
+            .build()
+            .err()
+            .map(|e| e.to_string().lines().last().unwrap_or_default().trim().to_string())
+    }
+
+    /// Whether an item's title or plain-text description matches.
+    pub fn matches(&self, title: &str, description: Option<&str>) -> bool {
+        let Some(matcher) = &self.matcher else {
+            return false;
+        };
+        matcher.is_match(title) || description.is_some_and(|d| matcher.is_match(d))
+    }
+
+    /// Byte ranges of every match in `text`, for highlighting.
+    pub fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(matcher) = &self.matcher else {
+            return Vec::new();
+        };
+        matcher
+            .find_iter(text)
+            .map(|m| m.range())
+            .filter(|r| !r.is_empty())
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Match as [`crate::app::App`] does, with the description as plain text.
+    fn hit(search: &Search, title: &str, description: Option<&str>) -> bool {
+        search.matches(title, description.map(crate::html::to_plain).as_deref())
+    }
+
+    #[test]
+    fn literal_search_is_case_insensitive() {
+        let search = Search::new("rust 1.", false);
+        assert!(hit(&search, "Announcing Rust 1.80", None));
+        assert!(!hit(&search, "Rust 2024", None), "`.` is literal");
+        assert_eq!(search.ranges("RUST 1.0 and rust 1.1"), [0..7, 13..20]);
+    }
+
+    #[test]
+    fn matches_description_text_not_markup() {
+        let search = Search::new("strong", false);
+        assert!(!hit(&search, "t", Some("<p><strong>bold</strong></p>")));
+        assert!(hit(&search, "t", Some("<p>A <em>strong</em> case</p>")));
+    }
+
+    #[test]
+    fn regex_mode() {
+        let search = Search::new(r"^v\d+", true);
+        assert!(hit(&search, "V12 released", None));
+        assert!(!hit(&search, "New v12", None));
+        assert_eq!(search.error(), None);
+    }
+
+    #[test]
+    fn invalid_or_empty_query_matches_nothing() {
+        let search = Search::new("(", true);
+        assert!(!hit(&search, "(", None));
+        assert!(search.error().is_some());
+
+        let search = Search::new("", false);
+        assert!(!hit(&search, "anything", None));
+        assert!(search.ranges("anything").is_empty());
+        assert_eq!(search.error(), None);
+    }
+}
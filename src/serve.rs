@@ -0,0 +1,378 @@
+//! Re-publishing the merged item list over HTTP.
+//!
+//! When the `[server]` table enables it, [`Server`] listens on a local
+//! address and serves the current item list — every source, de-duplicated
+//! and filtered, newest first — as RSS 2.0, Atom 1.0 and JSON Feed 1.1, so
+//! other feed readers can subscribe to one feed instead of many.
+//!
+//! [`crate::sinks`] calls [`Server::publish`] whenever the list changes;
+//! each request renders the latest list it was given.
+//!
+//! ## For contributors
+//!
+//! * Requests are answered on one background thread.  The documents are
+//!   small and readers poll rarely, so there is no need for more.
+//! * Responses carry an `ETag` that changes with every publish, so readers
+//!   that send `If-None-Match` get `304 Not Modified` between updates.
+//! * The source name of each item becomes a category (RSS, Atom) or tag
+//!   (JSON Feed), as there is no standard per-item "source title".
+
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+
+use anyhow::{anyhow, Context, Result};
+use atom_syndication::{CategoryBuilder, EntryBuilder, FeedBuilder, LinkBuilder, PersonBuilder, Text};
+use chrono::Utc;
+use rss::extension::dublincore::DublinCoreExtensionBuilder;
+use rss::{CategoryBuilder as RssCategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::config::ServerConfig;
+use crate::source::FeedItem;
+
+/// The items currently served, and how many times they have changed.
+/// Replaced whole on every publish, so requests can keep answering from
+/// the one they started with without holding the lock.
+#[derive(Default)]
+struct Snapshot {
+    items: Vec<FeedItem>,
+    version: u64,
+}
+
+/// Settings fixed when the server starts.
+struct Site {
+    title: String,
+    rss_path: String,
+    atom_path: String,
+    json_path: String,
+    /// Part of every `ETag`, so that tags from before a restart never match.
+    started: i64,
+}
+
+/// A running feed server.  Stops when dropped.
+pub struct Server {
+    http: Arc<tiny_http::Server>,
+    snapshot: Arc<RwLock<Arc<Snapshot>>>,
+    max_items: usize,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    /// Listen on `config.listen` and start answering requests.  Until the
+    /// first [`Server::publish`] the feeds are empty.
+    pub fn start(config: &ServerConfig) -> Result<Self> {
+        let http = tiny_http::Server::http(&config.listen)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("listening on {}", config.listen))?;
+        let http = Arc::new(http);
+        let snapshot = Arc::new(RwLock::new(Arc::new(Snapshot::default())));
+        let site = Site {
+            title: config.title.clone(),
+            rss_path: config.rss_path.clone(),
+            atom_path: config.atom_path.clone(),
+            json_path: config.json_path.clone(),
+            started: Utc::now().timestamp_millis(),
+        };
+
+        let (requests, items) = (Arc::clone(&http), Arc::clone(&snapshot));
+        let thread = thread::Builder::new()
+            .name("server".into())
+            .spawn(move || {
+                for request in requests.incoming_requests() {
+                    // Cloned so that a slow reader never holds up `publish`.
+                    let snapshot = Arc::clone(&items.read().unwrap());
+                    // A reader that hangs up early is its own problem.
+                    let _ = respond(request, &site, &snapshot);
+                }
+            })?;
+
+        Ok(Self {
+            http,
+            snapshot,
+            max_items: config.max_items,
+            thread: Some(thread),
+        })
+    }
+
+    /// The address the server is listening on.
+    pub fn addr(&self) -> Option<std::net::SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Serve `items` from now on, newest first and at most
+    /// `max_items` of them.
+    pub fn publish(&self, items: &[FeedItem]) {
+        let mut items = items.to_vec();
+        // Pinned items come first in the app; readers expect date order.
+        items.sort();
+        if self.max_items > 0 {
+            items.truncate(self.max_items);
+        }
+        let mut snapshot = self.snapshot.write().unwrap();
+        let version = snapshot.version + 1;
+        *snapshot = Arc::new(Snapshot { items, version });
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.http.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answer one request from `snapshot`.
+fn respond(request: Request, site: &Site, snapshot: &Snapshot) -> std::io::Result<()> {
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        return request.respond(Response::empty(405).with_header(header("Allow", "GET, HEAD")));
+    }
+    let path = request.url().split('?').next().unwrap_or_default();
+    // An empty path in the config turns that format off.
+    let is = |configured: &str| !configured.is_empty() && path == configured;
+    type Render = fn(&Site, &str, &[FeedItem]) -> String;
+    let (render, content_type): (Render, _) = if is(&site.rss_path) {
+        (rss, "application/rss+xml; charset=utf-8")
+    } else if is(&site.atom_path) {
+        (atom, "application/atom+xml; charset=utf-8")
+    } else if is(&site.json_path) {
+        (json_feed, "application/feed+json; charset=utf-8")
+    } else {
+        return request.respond(Response::empty(404));
+    };
+
+    let etag = format!("\"{}-{}\"", site.started, snapshot.version);
+    let unchanged = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("If-None-Match") && h.value.as_str() == etag);
+    if unchanged {
+        return request.respond(Response::empty(304).with_header(header("ETag", &etag)));
+    }
+
+    // Links back to the server use the host name the reader used.
+    let host = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Host"))
+        .map(|h| h.value.to_string())
+        .unwrap_or_else(|| "localhost".into());
+    let base = format!("http://{host}");
+    let body = render(site, &base, &snapshot.items);
+    request.respond(
+        Response::from_string(body)
+            .with_header(header("Content-Type", content_type))
+            .with_header(header("ETag", &etag)),
+    )
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+/// Render `items` as an RSS 2.0 document.
+fn rss(site: &Site, base: &str, items: &[FeedItem]) -> String {
+    let items: Vec<rss::Item> = items
+        .iter()
+        .map(|item| {
+            ItemBuilder::default()
+                .title(item.title.clone())
+                .link(item.link.clone())
+                .description(item.description.clone())
+                .guid(GuidBuilder::default().value(item.id.clone()).permalink(false).build())
+                .pub_date(item.published.map(|d| d.to_rfc2822()))
+                .categories(vec![RssCategoryBuilder::default().name(item.source_name.clone()).build()])
+                .dublin_core_ext(item.author.as_ref().map(|author| {
+                    DublinCoreExtensionBuilder::default().creators(vec![author.clone()]).build()
+                }))
+                .build()
+        })
+        .collect();
+    ChannelBuilder::default()
+        .title(site.title.clone())
+        .link(format!("{base}/"))
+        .description(format!("Items merged from every feed {} follows", site.title))
+        .last_build_date(Some(Utc::now().to_rfc2822()))
+        .items(items)
+        .build()
+        .to_string()
+}
+
+/// Render `items` as an Atom 1.0 document.
+fn atom(site: &Site, base: &str, items: &[FeedItem]) -> String {
+    let now = Utc::now().fixed_offset();
+    let entries: Vec<_> = items
+        .iter()
+        .map(|item| {
+            let published = item.published.map(|d| d.fixed_offset());
+            EntryBuilder::default()
+                .id(item.id.clone())
+                .title(item.title.clone())
+                .updated(published.unwrap_or(now))
+                .published(published)
+                .links(
+                    item.link
+                        .iter()
+                        .map(|href| LinkBuilder::default().href(href.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .summary(item.description.clone().map(Text::html))
+                .authors(
+                    item.author
+                        .iter()
+                        .map(|name| PersonBuilder::default().name(name.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .categories(vec![CategoryBuilder::default().term(item.source_name.clone()).build()])
+                .build()
+        })
+        .collect();
+    let self_link = format!("{base}{}", site.atom_path);
+    FeedBuilder::default()
+        .id(self_link.clone())
+        .title(site.title.clone())
+        .updated(items.iter().filter_map(|i| i.published).max().map_or(now, |d| d.fixed_offset()))
+        .links(vec![LinkBuilder::default().href(self_link).rel("self").build()])
+        .entries(entries)
+        .build()
+        .to_string()
+}
+
+/// Render `items` as a JSON Feed 1.1 document.
+fn json_feed(site: &Site, base: &str, items: &[FeedItem]) -> String {
+    let items: Vec<_> = items
+        .iter()
+        .map(|item| {
+            let mut entry = serde_json::json!({
+                "id": item.id,
+                "title": item.title,
+                "tags": [item.source_name],
+            });
+            let fields = [
+                ("url", item.link.clone()),
+                ("content_html", item.description.clone()),
+                ("date_published", item.published.map(|d| d.to_rfc3339())),
+            ];
+            for (key, value) in fields {
+                if let Some(value) = value {
+                    entry[key] = value.into();
+                }
+            }
+            if let Some(name) = &item.author {
+                entry["authors"] = serde_json::json!([{ "name": name }]);
+            }
+            entry
+        })
+        .collect();
+    serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": site.title,
+        "feed_url": format!("{base}{}", site.json_path),
+        "items": items,
+    })
+    .to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{AtomSource, DataSource, HttpClient, JsonFeedSource, RssSource};
+    use crate::testutil::make_item;
+    use chrono::{TimeZone, Utc};
+
+    /// An item with every field set, so that each is checked on the way
+    /// through a feed.
+    fn item(id: &str, hour: u32) -> FeedItem {
+        let link = format!("https://example.com/{id}");
+        let published = Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap();
+        FeedItem {
+            description: Some(format!("<p>About <b>{id}</b></p>")),
+            link: Some(link.clone()),
+            author: Some("Ann".into()),
+            ..make_item(&link, &format!("Item {id}"), Some(published))
+        }
+    }
+
+    fn start() -> (Server, String) {
+        let config = ServerConfig {
+            listen: "127.0.0.1:0".into(),
+            ..ServerConfig::default()
+        };
+        let server = Server::start(&config).unwrap();
+        let base = format!("http://{}", server.addr().unwrap());
+        (server, base)
+    }
+
+    /// What our own sources read back, with the label they were given.
+    fn read_back(items: &[FeedItem]) -> Vec<FeedItem> {
+        items
+            .iter()
+            .map(|i| FeedItem {
+                source_name: "served".into(),
+                ..i.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn serves_every_format_newest_first() {
+        let (server, base) = start();
+        let items = [item("a", 9), item("b", 10)];
+        server.publish(&items);
+        let expected = read_back(&[item("b", 10), item("a", 9)]);
+
+        let http = HttpClient::new();
+        let sources: [Box<dyn DataSource>; 3] = [
+            Box::new(RssSource::new(format!("{base}/rss.xml"), "served")),
+            Box::new(AtomSource::new(format!("{base}/atom.xml"), "served")),
+            Box::new(JsonFeedSource::new(format!("{base}/feed.json"), "served")),
+        ];
+        for source in &sources {
+            assert_eq!(source.fetch(&http).unwrap().items, expected, "{}", source.url());
+        }
+    }
+
+    #[test]
+    fn updates_live_and_answers_unchanged_with_304() {
+        let (server, base) = start();
+        let source = JsonFeedSource::new(format!("{base}/feed.json"), "served");
+        let http = HttpClient::new();
+
+        server.publish(&[item("a", 9)]);
+        assert_eq!(source.fetch(&http).unwrap().items.len(), 1);
+        assert!(source.fetch(&http).unwrap().items.is_empty(), "304: nothing new");
+        assert_eq!(http.last_status(source.url()).map(|s| s.as_u16()), Some(304));
+
+        server.publish(&[item("a", 9), item("b", 10)]);
+        let items = source.fetch(&http).unwrap().items;
+        assert_eq!(items[0].title, "Item b");
+        assert_eq!(items.len(), 2);
+    }
+
+    #[test]
+    fn caps_items_and_rejects_unknown_paths() {
+        let config = ServerConfig {
+            listen: "127.0.0.1:0".into(),
+            max_items: 1,
+            atom_path: String::new(),
+            ..ServerConfig::default()
+        };
+        let server = Server::start(&config).unwrap();
+        let base = format!("http://{}", server.addr().unwrap());
+        server.publish(&[item("a", 9), item("b", 10)]);
+
+        let http = HttpClient::new();
+        let items = RssSource::new(format!("{base}/rss.xml"), "served").fetch(&http).unwrap().items;
+        assert_eq!(items, read_back(&[item("b", 10)]));
+        for path in ["/atom.xml", "/", "/nope"] {
+            let url = format!("{base}{path}");
+            assert!(http.fetch(&url, |_| Ok(())).is_err(), "{path}");
+            assert_eq!(http.last_status(&url).map(|s| s.as_u16()), Some(404));
+        }
+    }
+}
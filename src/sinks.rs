@@ -0,0 +1,95 @@
+//! Everything new items are passed on to besides the screen: the feed
+//! server, webhooks and notifications.
+//!
+//! The TUI, `--headless` and daemon loops each hold one [`Sinks`] and tell
+//! it when items arrive or the rules change, so each output is wired up
+//! once.
+//!
+//! ## For contributors
+//!
+//! * A new output gets a field here, set up in [`Sinks::start`] and fed from
+//!   [`Sinks::merged`].  Outputs must not block: do slow work on a thread
+//!   of their own, as [`crate::webhook`] does.
+
+use anyhow::{Context, Result};
+
+use crate::app::App;
+use crate::config::Config;
+use crate::notify::{Delivery, Notifier};
+use crate::serve::Server;
+use crate::source::FeedItem;
+use crate::webhook::Webhook;
+
+/// The outputs configured in the config file.  Those not configured are
+/// absent, so `Sinks::default()` does nothing.
+#[derive(Default)]
+pub struct Sinks {
+    /// The feed server, when `[server]` enables it.
+    pub server: Option<Server>,
+    /// One per `[[webhook]]` table.
+    pub webhooks: Vec<Webhook>,
+    /// Bell, terminal and command notifications.
+    pub notifier: Option<Notifier>,
+}
+
+impl Sinks {
+    /// Start every output `config` asks for, notifying in the ways
+    /// `delivery` allows.
+    pub fn start(config: &Config, delivery: Delivery) -> Result<Self> {
+        let server = if config.server.enabled {
+            Some(Server::start(&config.server).context("starting the feed server")?)
+        } else {
+            None
+        };
+        let webhooks = config
+            .webhooks
+            .iter()
+            .enumerate()
+            .map(|(i, webhook)| Webhook::start(webhook).with_context(|| format!("in webhook {}", i + 1)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            server,
+            webhooks,
+            notifier: Some(Notifier::new(config, delivery)),
+        })
+    }
+
+    /// Pass on `added`, the items [`App::merge_items`] just returned for
+    /// the feed at `url`.  Items a rule hides are not sent anywhere.
+    pub fn merged(&mut self, app: &App, url: &str, added: &[FeedItem]) {
+        if added.is_empty() {
+            return;
+        }
+        self.list_changed(app);
+        let visible: Vec<FeedItem> = added.iter().filter(|item| !app.verdict(item).hide).cloned().collect();
+        if !visible.is_empty() {
+            for webhook in &self.webhooks {
+                webhook.send(&visible);
+            }
+        }
+        if let Some(notifier) = &mut self.notifier {
+            notifier.merged(app, url, added);
+        }
+    }
+
+    /// Called on every turn of the event loop, for work that waits.
+    pub fn tick(&mut self) {
+        if let Some(notifier) = &mut self.notifier {
+            notifier.tick();
+        }
+    }
+
+    /// Re-publish the whole item list, e.g. after the rules change.
+    pub fn list_changed(&self, app: &App) {
+        if let Some(server) = &self.server {
+            server.publish(&app.items);
+        }
+    }
+
+    /// Delivery failures since the last call, each saying what failed.
+    pub fn take_errors(&mut self) -> Vec<String> {
+        let mut errors: Vec<String> = self.webhooks.iter().flat_map(Webhook::take_errors).collect();
+        errors.extend(self.notifier.iter_mut().flat_map(Notifier::take_errors));
+        errors
+    }
+}
@@ -0,0 +1,229 @@
+//! Atom 1.0 feed source implementation.
+//!
+//! Mirrors [`super::RssSource`]: a small config struct, a pure
+//! [`AtomSource::parse_feed`] function that tests can call directly, and a
+//! [`DataSource`] impl that does the HTTP work.
+
+use anyhow::Result;
+use chrono::Utc;
+
+use super::{DataSource, FeedHints, FeedItem, Fetched, HttpClient};
+
+/// An Atom feed data source.
+///
+/// Fetches and parses an Atom 1.0 feed over HTTP using the
+/// [`atom_syndication`] crate.
+pub struct AtomSource {
+    /// The feed URL to poll.
+    pub url: String,
+    /// A human-readable label shown in the UI next to each item.
+    pub label: String,
+}
+
+impl AtomSource {
+    /// Create a new Atom source.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` — full URL of the Atom feed (e.g.
+    ///   `https://github.com/rust-lang/rust/releases.atom`).
+    /// * `label` — short name displayed in the TUI for items from this feed.
+    pub fn new(url: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Parse an already-fetched [`atom_syndication::Feed`] into [`FeedItem`]s.
+    ///
+    /// Like [`super::RssSource::parse_channel`], this does no I/O.
+    pub fn parse_feed(feed: &atom_syndication::Feed, label: &str) -> Vec<FeedItem> {
+        feed.entries()
+            .iter()
+            .map(|entry| {
+                // rel="alternate" is the link to the human-readable page.  The
+                // crate fills in "alternate" when the attribute is absent.
+                let link = entry
+                    .links()
+                    .iter()
+                    .find(|l| l.rel() == "alternate")
+                    .or_else(|| entry.links().first())
+                    .map(|l| l.href().to_string());
+
+                // <id> is mandatory in Atom, but fall back to the link for
+                // sloppy feeds that omit it.
+                let id = Some(entry.id())
+                    .filter(|id| !id.is_empty())
+                    .map(String::from)
+                    .or_else(|| link.clone())
+                    .unwrap_or_default();
+
+                // Prefer <published>; otherwise use <updated> unless it is the
+                // crate's epoch placeholder for a missing element.
+                let published = entry
+                    .published()
+                    .copied()
+                    .or_else(|| Some(*entry.updated()).filter(|d| d.timestamp() != 0))
+                    .map(|d| d.with_timezone(&Utc));
+
+                // Prefer the full <content>, falling back to <summary>.
+                let description = entry
+                    .content()
+                    .and_then(|c| c.value())
+                    .or_else(|| entry.summary().map(|s| s.as_str()))
+                    .map(String::from);
+
+                let title = entry.title().as_str();
+                let authors: Vec<&str> = entry.authors().iter().map(|a| a.name()).collect();
+
+                FeedItem {
+                    id,
+                    title: if title.is_empty() { "(untitled)" } else { title }.to_string(),
+                    description,
+                    link,
+                    published,
+                    source_name: label.to_string(),
+                    author: (!authors.is_empty()).then(|| authors.join(", ")),
+                }
+            })
+            .collect()
+    }
+}
+
+impl DataSource for AtomSource {
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch(&self, http: &HttpClient) -> Result<Fetched> {
+        let fetched = http.fetch(&self.url, |body| {
+            let feed = atom_syndication::Feed::read_from(body)?;
+            Ok(Fetched {
+                items: Self::parse_feed(&feed, &self.label),
+                // The format has no polling hints.
+                hints: Some(FeedHints::default()),
+            })
+        })?;
+        Ok(fetched.unwrap_or_default())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn parse(xml: &str) -> Vec<FeedItem> {
+        let feed = atom_syndication::Feed::read_from(xml.as_bytes()).unwrap();
+        AtomSource::parse_feed(&feed, "TestFeed")
+    }
+
+    #[test]
+    fn parse_feed_extracts_entries() {
+        let items = parse(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Test Feed</title>
+  <id>urn:feed</id>
+  <updated>2024-01-02T12:00:00Z</updated>
+  <entry>
+    <title>First Post</title>
+    <id>urn:entry:1</id>
+    <link rel="alternate" href="https://example.com/1"/>
+    <link rel="edit" href="https://example.com/1/edit"/>
+    <published>2024-01-01T00:00:00Z</published>
+    <updated>2024-01-01T06:00:00Z</updated>
+    <summary>First summary</summary>
+    <author><name>Ada</name></author>
+  </entry>
+  <entry>
+    <title>Second Post</title>
+    <id>urn:entry:2</id>
+    <link href="https://example.com/2"/>
+    <updated>2024-01-02T12:00:00Z</updated>
+    <summary>Short</summary>
+    <content type="html">&lt;p&gt;Full text&lt;/p&gt;</content>
+  </entry>
+</feed>"#,
+        );
+
+        assert_eq!(items.len(), 2);
+
+        assert_eq!(items[0].id, "urn:entry:1");
+        assert_eq!(items[0].title, "First Post");
+        assert_eq!(items[0].link.as_deref(), Some("https://example.com/1"));
+        assert_eq!(items[0].description.as_deref(), Some("First summary"));
+        assert_eq!(items[0].source_name, "TestFeed");
+        assert_eq!(items[0].author.as_deref(), Some("Ada"));
+        assert_eq!(items[0].published, Some(ts("2024-01-01T00:00:00Z")), "published wins over updated");
+
+        assert_eq!(items[1].id, "urn:entry:2");
+        assert_eq!(items[1].link.as_deref(), Some("https://example.com/2"));
+        assert!(items[1].author.is_none());
+        assert_eq!(items[1].description.as_deref(), Some("<p>Full text</p>"), "content wins over summary");
+        assert_eq!(items[1].published, Some(ts("2024-01-02T12:00:00Z")), "falls back to updated");
+    }
+
+    #[test]
+    fn prefers_alternate_link_over_first_link() {
+        let items = parse(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>T</title><id>f</id><updated>2024-01-01T00:00:00Z</updated>
+  <entry>
+    <title>E</title><id>e</id><updated>2024-01-01T00:00:00Z</updated>
+    <link rel="enclosure" href="https://example.com/audio.mp3"/>
+    <link rel="alternate" type="text/html" href="https://example.com/page"/>
+  </entry>
+</feed>"#,
+        );
+
+        assert_eq!(items[0].link.as_deref(), Some("https://example.com/page"));
+    }
+
+    #[test]
+    fn falls_back_to_link_when_no_id() {
+        let items = parse(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>T</title><id>f</id><updated>2024-01-01T00:00:00Z</updated>
+  <entry>
+    <title>No ID</title>
+    <link href="https://example.com/no-id"/>
+  </entry>
+</feed>"#,
+        );
+
+        assert_eq!(items[0].id, "https://example.com/no-id");
+        assert!(items[0].published.is_none(), "missing <updated> is not the epoch");
+    }
+
+    #[test]
+    fn handles_missing_title() {
+        let items = parse(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>T</title><id>f</id><updated>2024-01-01T00:00:00Z</updated>
+  <entry><id>e1</id><updated>2024-01-01T00:00:00Z</updated></entry>
+</feed>"#,
+        );
+
+        assert_eq!(items[0].title, "(untitled)");
+    }
+
+    #[test]
+    fn name_returns_label() {
+        let src = AtomSource::new("http://example.com/feed.atom", "My Feed");
+        assert_eq!(src.name(), "My Feed");
+    }
+}
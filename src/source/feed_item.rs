@@ -11,6 +11,7 @@
 //! in your source's `fetch()` implementation.
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
 /// A single feed entry, normalised from any data source.
@@ -23,7 +24,7 @@ use std::cmp::Ordering;
 ///
 /// `FeedItem` implements [`Ord`] for **reverse-chronological** ordering:
 /// newer items sort before older ones, and items without a date sort last.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct FeedItem {
     /// Unique identifier used for de-duplication.
     ///
@@ -48,6 +49,9 @@ pub struct FeedItem {
 
     /// Name of the source or feed this came from (e.g. "BBC News").
     pub source_name: String,
+
+    /// Author name(s), comma-separated when there are several.
+    pub author: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -76,27 +80,16 @@ impl PartialOrd for FeedItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::make_item;
     use chrono::TimeZone;
 
-    /// Shorthand constructor for tests.
-    pub fn make_item(id: &str, title: &str, published: Option<DateTime<Utc>>) -> FeedItem {
-        FeedItem {
-            id: id.to_string(),
-            title: title.to_string(),
-            description: None,
-            link: None,
-            published,
-            source_name: "test".to_string(),
-        }
-    }
-
     #[test]
     fn sort_reverse_chronological() {
         let old = make_item("1", "Old", Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
         let mid = make_item("2", "Mid", Some(Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap()));
         let new = make_item("3", "New", Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()));
 
-        let mut items = vec![old.clone(), new.clone(), mid.clone()];
+        let mut items = [old.clone(), new.clone(), mid.clone()];
         items.sort();
 
         assert_eq!(items[0].id, "3", "newest first");
@@ -109,7 +102,7 @@ mod tests {
         let dated = make_item("1", "Dated", Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
         let undated = make_item("2", "Undated", None);
 
-        let mut items = vec![undated.clone(), dated.clone()];
+        let mut items = [undated.clone(), dated.clone()];
         items.sort();
 
         assert_eq!(items[0].id, "1", "dated item should come first");
@@ -0,0 +1,159 @@
+//! Polling hints published by feeds.
+//!
+//! RSS 2.0 channels may carry `<ttl>` (minutes a copy may be cached),
+//! `<skipHours>` and `<skipDays>` (when not to bother polling, in GMT), and
+//! RSS 1.0 / 2.0 channels may use the syndication module's
+//! `sy:updatePeriod` / `sy:updateFrequency` to say how often they change.
+//! [`FeedHints`] collects them; [`crate::poll`] uses them to schedule the
+//! next fetch.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, DurationRound, TimeDelta, Timelike, Utc, Weekday};
+
+/// Scheduling hints from one fetched document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedHints {
+    /// Don't poll more often than this: the larger of `<ttl>` and the
+    /// `sy:` update interval.
+    pub min_interval: Option<Duration>,
+    /// Hours of the day (UTC, 0–23) in which not to poll.
+    pub skip_hours: Vec<u32>,
+    /// Days of the week (UTC) on which not to poll.
+    pub skip_days: Vec<Weekday>,
+}
+
+impl FeedHints {
+    /// Hints from an RSS 2.0 channel.
+    pub fn from_channel(channel: &rss::Channel) -> Self {
+        let ttl = channel.ttl().and_then(parse_ttl);
+        let sy = channel.syndication_ext().and_then(|sy| {
+            use rss::extension::syndication::UpdatePeriod;
+            let period = match sy.period() {
+                UpdatePeriod::Hourly => "hourly",
+                UpdatePeriod::Daily => "daily",
+                UpdatePeriod::Weekly => "weekly",
+                UpdatePeriod::Monthly => "monthly",
+                UpdatePeriod::Yearly => "yearly",
+            };
+            update_interval(period, sy.frequency())
+        });
+        Self {
+            min_interval: ttl.max(sy),
+            skip_hours: channel
+                .skip_hours()
+                .iter()
+                .filter_map(|h| h.trim().parse().ok())
+                // RSS 2.0 says 0–23, but some feeds write 24 for midnight.
+                .map(|h: u32| h % 24)
+                .collect(),
+            skip_days: channel
+                .skip_days()
+                .iter()
+                .filter_map(|d| d.trim().parse().ok())
+                .collect(),
+        }
+    }
+
+    /// The first instant at or after `earliest` that falls outside the
+    /// skipped hours and days.  Returns `earliest` if every hour is skipped.
+    pub fn next_allowed(&self, earliest: DateTime<Utc>) -> DateTime<Utc> {
+        let mut t = earliest;
+        // A week of hours is enough to find any allowed slot.
+        for _ in 0..=24 * 7 {
+            let hour_start = t.duration_trunc(TimeDelta::hours(1)).unwrap_or(t);
+            if self.skip_days.contains(&t.weekday()) {
+                t = hour_start + TimeDelta::hours(24 - i64::from(t.hour()));
+            } else if self.skip_hours.contains(&t.hour()) {
+                t = hour_start + TimeDelta::hours(1);
+            } else {
+                return t;
+            }
+        }
+        earliest
+    }
+}
+
+/// Parse a `<ttl>` value (whole minutes).
+pub fn parse_ttl(minutes: &str) -> Option<Duration> {
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
+/// Interval implied by `sy:updatePeriod` and `sy:updateFrequency`: the
+/// feed updates `frequency` times per `period`.
+pub fn update_interval(period: &str, frequency: u32) -> Option<Duration> {
+    let period_secs: u64 = match period.trim() {
+        "hourly" => 3600,
+        "daily" => 86_400,
+        "weekly" => 7 * 86_400,
+        "monthly" => 30 * 86_400,
+        "yearly" => 365 * 86_400,
+        _ => return None,
+    };
+    Some(Duration::from_secs(period_secs / u64::from(frequency.max(1))))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn reads_channel_hints() {
+        let xml = r#"<rss version="2.0" xmlns:sy="http://purl.org/rss/1.0/modules/syndication/">
+  <channel>
+    <title>t</title><link>https://x/</link><description>d</description>
+    <ttl>30</ttl>
+    <sy:updatePeriod>hourly</sy:updatePeriod>
+    <sy:updateFrequency>2</sy:updateFrequency>
+    <skipHours><hour>0</hour><hour>24</hour><hour>5</hour></skipHours>
+    <skipDays><day>Saturday</day><day>Sunday</day></skipDays>
+  </channel>
+</rss>"#;
+        let channel = rss::Channel::read_from(xml.as_bytes()).unwrap();
+        let hints = FeedHints::from_channel(&channel);
+
+        assert_eq!(hints.min_interval, Some(Duration::from_secs(30 * 60)));
+        assert_eq!(hints.skip_hours, [0, 0, 5]);
+        assert_eq!(hints.skip_days, [Weekday::Sat, Weekday::Sun]);
+    }
+
+    #[test]
+    fn update_interval_divides_period() {
+        assert_eq!(update_interval("daily", 4), Some(Duration::from_secs(6 * 3600)));
+        assert_eq!(update_interval("hourly", 0), Some(Duration::from_secs(3600)));
+        assert_eq!(update_interval("fortnightly", 1), None);
+    }
+
+    #[test]
+    fn next_allowed_skips_hours_and_days() {
+        let hints = FeedHints {
+            skip_hours: vec![22, 23],
+            skip_days: vec![Weekday::Sat],
+            ..Default::default()
+        };
+        // Friday 2025-01-03 22:30 → skip 22, 23 and all of Saturday.
+        let fri = Utc.with_ymd_and_hms(2025, 1, 3, 22, 30, 0).unwrap();
+        assert_eq!(
+            hints.next_allowed(fri),
+            Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap()
+        );
+        let ok = Utc.with_ymd_and_hms(2025, 1, 3, 12, 15, 0).unwrap();
+        assert_eq!(hints.next_allowed(ok), ok);
+    }
+
+    #[test]
+    fn next_allowed_gives_up_when_everything_is_skipped() {
+        let hints = FeedHints {
+            skip_hours: (0..24).collect(),
+            ..Default::default()
+        };
+        let t = Utc.with_ymd_and_hms(2025, 1, 3, 12, 0, 0).unwrap();
+        assert_eq!(hints.next_allowed(t), t);
+    }
+}
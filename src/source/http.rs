@@ -0,0 +1,328 @@
+//! HTTP fetching with conditional GET.
+//!
+//! [`HttpClient`] remembers each feed URL's `ETag` and `Last-Modified`
+//! response headers (its *validators*) and sends them back as
+//! `If-None-Match` / `If-Modified-Since`.  A `304 Not Modified` reply has
+//! no body and means "no new items", which saves bandwidth and keeps hosts
+//! that rate-limit full downloads happy.
+//!
+//! ## For contributors
+//!
+//! * Sources fetch through [`HttpClient::fetch`] with a parse callback.
+//!   Validators are only stored once the body has parsed, so a broken
+//!   response is downloaded again in full next time rather than hidden
+//!   behind a 304.
+//! * A non-success status is returned as an [`HttpError`] inside the
+//!   `anyhow::Error`, so callers can `downcast_ref` it to see the status
+//!   and any `Retry-After` delay.
+//! * The status of the last response per URL is kept for
+//!   [`HttpClient::last_status`], so the poller can report it without
+//!   every source having to pass it along.
+//! * Validators can be persisted to a JSON file so they survive restarts.
+//!   Only do this when item history is persisted too; otherwise a 304 on
+//!   the first poll would leave the list empty.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Give up on a request after this long.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Cache validators for one URL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+/// A response with a non-success status code.
+#[derive(Debug)]
+pub struct HttpError {
+    pub status: StatusCode,
+    /// How long the server asked us to wait, from the `Retry-After`
+    /// header of a 429 or 503 response.
+    pub retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP {}", self.status)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Blocking HTTP client shared by all sources.
+pub struct HttpClient {
+    client: Client,
+    /// Validators by URL.
+    validators: Mutex<HashMap<String, Validators>>,
+    /// Where validators are saved, if anywhere.
+    path: Option<PathBuf>,
+    /// Status of the most recent response by URL.
+    statuses: Mutex<HashMap<String, StatusCode>>,
+}
+
+impl HttpClient {
+    /// A client that keeps validators in memory only.
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+                .timeout(TIMEOUT)
+                .build()
+                .expect("default TLS backend is available"),
+            validators: Mutex::new(HashMap::new()),
+            path: None,
+            statuses: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A client that loads validators from, and saves them to, `path`.
+    ///
+    /// A missing or unreadable file just means starting from scratch.
+    pub fn persistent(path: &Path) -> Self {
+        let validators = fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            validators: Mutex::new(validators),
+            path: Some(path.to_path_buf()),
+            ..Self::new()
+        }
+    }
+
+    /// GET `url` and hand the body to `parse`.
+    ///
+    /// Returns `Ok(None)` if the server answered `304 Not Modified`.  Any
+    /// other non-success status is an [`HttpError`].
+    pub fn fetch<T>(&self, url: &str, parse: impl FnOnce(&[u8]) -> Result<T>) -> Result<Option<T>> {
+        let cached = self.validators.lock().unwrap().get(url).cloned().unwrap_or_default();
+
+        let mut request = self.client.get(url);
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(date) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, date);
+        }
+        let mut response = match request.send() {
+            Ok(response) => response,
+            Err(e) => {
+                // No response, so no status to show next to the error.
+                self.statuses.lock().unwrap().remove(url);
+                return Err(e.into());
+            }
+        };
+        let status = response.status();
+        self.statuses.lock().unwrap().insert(url.to_string(), status);
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let retry_after = matches!(
+                status,
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+            )
+            .then(|| response.headers().get(RETRY_AFTER)?.to_str().ok())
+            .flatten()
+            .and_then(|v| parse_retry_after(v, Utc::now()));
+            return Err(HttpError { status, retry_after }.into());
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        let fresh = Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        let mut body = Vec::new();
+        response.read_to_end(&mut body)?;
+        let parsed = parse(&body)?;
+
+        if fresh != cached {
+            let mut validators = self.validators.lock().unwrap();
+            if fresh == Validators::default() {
+                validators.remove(url);
+            } else {
+                validators.insert(url.to_string(), fresh);
+            }
+            self.save(&validators);
+        }
+        Ok(Some(parsed))
+    }
+
+    /// The status code of the last response received from `url`, or `None`
+    /// if the last request got no response.
+    pub fn last_status(&self, url: &str) -> Option<StatusCode> {
+        self.statuses.lock().unwrap().get(url).copied()
+    }
+
+    /// Write validators to disk.  Best-effort: failing costs one full
+    /// download per feed after the next restart, which is not worth an
+    /// error message.
+    fn save(&self, validators: &HashMap<String, Validators>) {
+        let Some(path) = &self.path else { return };
+        let write = || -> Result<()> {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, serde_json::to_vec(validators)?)?;
+            fs::rename(&tmp, path)?;
+            Ok(())
+        };
+        let _ = write();
+    }
+}
+
+/// Parse a `Retry-After` value: either whole seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means "now".
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{Response, Server};
+
+    /// Serves `body` with an ETag and Last-Modified, honouring both
+    /// conditional headers.
+    fn caching_server() -> Server {
+        Server::start(|req| {
+            let fresh = req.header("if-none-match") == Some("\"v1\"")
+                || req.header("if-modified-since") == Some("Wed, 01 Jan 2025 00:00:00 GMT");
+            if fresh {
+                Response::status(304)
+            } else {
+                Response::ok("body")
+                    .header("ETag", "\"v1\"")
+                    .header("Last-Modified", "Wed, 01 Jan 2025 00:00:00 GMT")
+            }
+        })
+    }
+
+    fn to_string(body: &[u8]) -> Result<String> {
+        Ok(String::from_utf8(body.to_vec())?)
+    }
+
+    #[test]
+    fn sends_validators_and_handles_not_modified() {
+        let server = caching_server();
+        let http = HttpClient::new();
+        let url = server.url("/feed");
+
+        assert_eq!(http.fetch(&url, to_string).unwrap().as_deref(), Some("body"));
+        assert_eq!(http.fetch(&url, to_string).unwrap(), None, "304 means nothing new");
+        assert_eq!(http.last_status(&url), Some(StatusCode::NOT_MODIFIED));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+        assert_eq!(
+            requests[1].header("if-modified-since"),
+            Some("Wed, 01 Jan 2025 00:00:00 GMT")
+        );
+    }
+
+    #[test]
+    fn validators_persist_across_clients() {
+        let server = caching_server();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("http-cache.json");
+        let url = server.url("/feed");
+
+        HttpClient::persistent(&path).fetch(&url, to_string).unwrap();
+        let http = HttpClient::persistent(&path);
+        assert_eq!(http.fetch(&url, to_string).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_failure_does_not_store_validators() {
+        let server = caching_server();
+        let http = HttpClient::new();
+        let url = server.url("/feed");
+
+        assert!(http.fetch(&url, |_| -> Result<()> { anyhow::bail!("bad") }).is_err());
+        assert!(http.fetch(&url, to_string).unwrap().is_some(), "full download again");
+    }
+
+    #[test]
+    fn error_status_is_an_error() {
+        let server = Server::start(|_| Response::status(500).header("Retry-After", "5"));
+        let err = HttpClient::new().fetch(&server.url("/"), to_string).unwrap_err();
+        let err = err.downcast_ref::<HttpError>().unwrap();
+        assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.retry_after, None, "only honoured on 429 / 503");
+    }
+
+    #[test]
+    fn transport_error_forgets_the_last_status() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let url = format!("http://127.0.0.1:{port}/feed");
+        let http = HttpClient::new();
+        http.statuses.lock().unwrap().insert(url.clone(), StatusCode::OK);
+
+        assert!(http.fetch(&url, to_string).is_err(), "nothing listens on {url}");
+        assert_eq!(http.last_status(&url), None);
+    }
+
+    #[test]
+    fn rate_limit_reports_retry_after() {
+        let server = Server::start(|_| Response::status(429).header("Retry-After", "120"));
+        let err = HttpClient::new().fetch(&server.url("/"), to_string).unwrap_err();
+        let err = err.downcast_ref::<HttpError>().unwrap();
+        assert_eq!(err.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn parses_retry_after_forms() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_retry_after(" 30 ", now), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
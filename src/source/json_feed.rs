@@ -0,0 +1,254 @@
+//! JSON Feed 1.0 / 1.1 source implementation.
+//!
+//! See <https://www.jsonfeed.org/version/1.1/>.  The document is
+//! deserialised into the [`JsonFeed`] structs below, then converted by the
+//! pure [`JsonFeedSource::parse_feed`] function.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{DataSource, FeedHints, FeedItem, Fetched, HttpClient};
+
+/// A JSON Feed data source.
+pub struct JsonFeedSource {
+    /// The feed URL to poll.
+    pub url: String,
+    /// A human-readable label shown in the UI next to each item.
+    pub label: String,
+}
+
+/// The subset of a JSON Feed document that we read.
+#[derive(Debug, Deserialize)]
+pub struct JsonFeed {
+    /// Feed-level authors (1.1), inherited by items that have none.
+    #[serde(default)]
+    pub authors: Vec<JsonFeedAuthor>,
+    /// Feed-level author (1.0, deprecated in 1.1).
+    pub author: Option<JsonFeedAuthor>,
+    /// The feed entries.
+    #[serde(default)]
+    pub items: Vec<JsonFeedItem>,
+}
+
+/// A single entry in a [`JsonFeed`].
+#[derive(Debug, Deserialize)]
+pub struct JsonFeedItem {
+    /// Unique id.  The spec says string, but some publishers emit numbers.
+    pub id: Option<serde_json::Value>,
+    /// Permalink to the item.
+    pub url: Option<String>,
+    /// Headline; optional for microblog-style feeds.
+    pub title: Option<String>,
+    /// Body as HTML.
+    pub content_html: Option<String>,
+    /// Body as plain text.
+    pub content_text: Option<String>,
+    /// Short plain-text summary.
+    pub summary: Option<String>,
+    /// RFC 3339 publication date.
+    pub date_published: Option<String>,
+    /// RFC 3339 modification date.
+    pub date_modified: Option<String>,
+    /// Item authors (1.1).
+    #[serde(default)]
+    pub authors: Vec<JsonFeedAuthor>,
+    /// Item author (1.0, deprecated in 1.1).
+    pub author: Option<JsonFeedAuthor>,
+}
+
+/// An author object; every field is optional.
+#[derive(Debug, Deserialize)]
+pub struct JsonFeedAuthor {
+    /// Display name.
+    pub name: Option<String>,
+}
+
+impl JsonFeedSource {
+    /// Create a new JSON Feed source.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` — full URL of the JSON Feed (e.g.
+    ///   `https://example.org/feed.json`).
+    /// * `label` — short name displayed in the TUI for items from this feed.
+    pub fn new(url: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Convert an already-deserialised [`JsonFeed`] into [`FeedItem`]s.
+    ///
+    /// Like [`super::RssSource::parse_channel`], this does no I/O.
+    pub fn parse_feed(feed: &JsonFeed, label: &str) -> Vec<FeedItem> {
+        let feed_author = author_names(&feed.authors, feed.author.as_ref());
+
+        feed.items
+            .iter()
+            .map(|item| {
+                let id = match &item.id {
+                    Some(serde_json::Value::String(s)) => Some(s.clone()),
+                    Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+                    _ => None,
+                }
+                .or_else(|| item.url.clone())
+                .unwrap_or_default();
+
+                // RFC 3339 per the spec; date_modified only if unpublished.
+                let published = item
+                    .date_published
+                    .as_deref()
+                    .or(item.date_modified.as_deref())
+                    .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                    .map(|dt| dt.with_timezone(&Utc));
+
+                FeedItem {
+                    id,
+                    title: item.title.clone().unwrap_or_else(|| "(untitled)".into()),
+                    description: item
+                        .content_html
+                        .as_ref()
+                        .or(item.content_text.as_ref())
+                        .or(item.summary.as_ref())
+                        .cloned(),
+                    link: item.url.clone(),
+                    published,
+                    source_name: label.to_string(),
+                    author: author_names(&item.authors, item.author.as_ref())
+                        .or_else(|| feed_author.clone()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Join the names from a 1.1 `authors` list, falling back to a 1.0 `author`.
+fn author_names(authors: &[JsonFeedAuthor], legacy: Option<&JsonFeedAuthor>) -> Option<String> {
+    let names: Vec<&str> = authors
+        .iter()
+        .chain(legacy)
+        .filter_map(|a| a.name.as_deref())
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+impl DataSource for JsonFeedSource {
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch(&self, http: &HttpClient) -> Result<Fetched> {
+        let fetched = http.fetch(&self.url, |body| {
+            let feed: JsonFeed = serde_json::from_slice(body)?;
+            Ok(Fetched {
+                items: Self::parse_feed(&feed, &self.label),
+                // The format has no polling hints.
+                hints: Some(FeedHints::default()),
+            })
+        })?;
+        Ok(fetched.unwrap_or_default())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Vec<FeedItem> {
+        let feed: JsonFeed = serde_json::from_str(json).unwrap();
+        JsonFeedSource::parse_feed(&feed, "TestFeed")
+    }
+
+    #[test]
+    fn parse_feed_extracts_items() {
+        let items = parse(
+            r#"{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Status",
+  "items": [
+    {
+      "id": "1",
+      "url": "https://example.com/1",
+      "title": "First",
+      "content_html": "<p>Hello</p>",
+      "content_text": "Hello",
+      "date_published": "2024-01-01T00:00:00Z",
+      "authors": [{"name": "Ada"}, {"name": "Grace"}]
+    },
+    {
+      "id": "2",
+      "title": "Second",
+      "content_text": "Plain only",
+      "date_modified": "2024-01-02T12:00:00+02:00"
+    }
+  ]
+}"#,
+        );
+
+        assert_eq!(items.len(), 2);
+
+        assert_eq!(items[0].id, "1");
+        assert_eq!(items[0].title, "First");
+        assert_eq!(items[0].link.as_deref(), Some("https://example.com/1"));
+        assert_eq!(items[0].description.as_deref(), Some("<p>Hello</p>"), "HTML wins over text");
+        assert_eq!(items[0].author.as_deref(), Some("Ada, Grace"));
+        assert_eq!(items[0].source_name, "TestFeed");
+        assert!(items[0].published.is_some());
+
+        assert_eq!(items[1].description.as_deref(), Some("Plain only"));
+        assert_eq!(
+            items[1].published.unwrap().to_rfc3339(),
+            "2024-01-02T10:00:00+00:00",
+            "falls back to date_modified, normalised to UTC"
+        );
+        assert!(items[1].link.is_none());
+    }
+
+    #[test]
+    fn supports_version_1_0_author_and_numeric_ids() {
+        let items = parse(
+            r#"{
+  "version": "https://jsonfeed.org/version/1",
+  "author": {"name": "Feed Author"},
+  "items": [
+    {"id": 42, "content_text": "x"},
+    {"id": "a", "content_text": "y", "author": {"name": "Item Author"}}
+  ]
+}"#,
+        );
+
+        assert_eq!(items[0].id, "42");
+        assert_eq!(items[0].author.as_deref(), Some("Feed Author"), "inherits feed author");
+        assert_eq!(items[1].author.as_deref(), Some("Item Author"));
+    }
+
+    #[test]
+    fn falls_back_to_url_when_no_id() {
+        let items = parse(r#"{"items": [{"url": "https://example.com/no-id", "title": "t"}]}"#);
+        assert_eq!(items[0].id, "https://example.com/no-id");
+    }
+
+    #[test]
+    fn handles_missing_title_and_invalid_date() {
+        let items = parse(r#"{"items": [{"id": "x", "date_published": "yesterday"}]}"#);
+        assert_eq!(items[0].title, "(untitled)");
+        assert!(items[0].published.is_none());
+        assert!(items[0].author.is_none());
+    }
+
+    #[test]
+    fn name_returns_label() {
+        let src = JsonFeedSource::new("http://example.com/feed.json", "My Feed");
+        assert_eq!(src.name(), "My Feed");
+    }
+}
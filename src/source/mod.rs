@@ -1,32 +1,92 @@
 //! Data source abstraction layer.
 //!
 //! This module defines the [`DataSource`] trait and the common [`FeedItem`]
-//! type.  Concrete source implementations live in sub-modules (currently only
-//! [`rss`]).
+//! type.  Concrete source implementations live in sub-modules ([`rss`],
+//! [`atom`], [`rdf`] and [`json_feed`]).
 //!
 //! ## For contributors — adding a new source
 //!
 //! 1. Create a new file in this directory (e.g. `atom.rs`).
 //! 2. Define a struct (e.g. `AtomSource`) and implement [`DataSource`] for it.
 //! 3. Add `mod atom;` below and re-export your struct in the `pub use` block.
-//! 4. Construct an instance in `main.rs` and add it to the `sources` vec.
+//! 4. Add a [`FeedKind`] variant and construct your source for it in
+//!    [`from_config`].
 //!
 //! That's it — the polling loop, de-duplication, and UI are all source-agnostic.
+//!
+//! Sources download through the shared [`HttpClient`] passed to
+//! [`DataSource::fetch`] rather than calling [`reqwest`] directly, so they
+//! get conditional GET (ETag / Last-Modified) for free.
 
+mod atom;
 mod feed_item;
+mod hints;
+mod http;
+mod json_feed;
+mod rdf;
 mod rss;
 
 // Re-export the public API of this module so callers can write
 // `use crate::source::{DataSource, FeedItem, RssSource};`
+pub use atom::AtomSource;
 pub use feed_item::FeedItem;
+pub use hints::FeedHints;
+pub use http::{HttpClient, HttpError};
+pub use json_feed::JsonFeedSource;
+pub use rdf::RdfSource;
 pub use rss::RssSource;
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::FeedConfig;
+
+/// Which [`DataSource`] implementation handles a configured feed.
+///
+/// Spelled as the `type` key in the config file and the `--type` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedKind {
+    /// RSS 2.0, also detecting Atom, RDF and JSON Feed served at the URL.
+    #[default]
+    Rss,
+    /// Atom 1.0.
+    Atom,
+    /// RSS 1.0 (RDF).
+    Rdf,
+    /// JSON Feed 1.0 / 1.1.
+    Json,
+}
+
+/// Build the data source for one configured feed.
+pub fn from_config(feed: &FeedConfig) -> Box<dyn DataSource> {
+    let (url, label) = (feed.url.clone(), feed.label());
+    match feed.kind {
+        FeedKind::Rss => Box::new(RssSource::new(url, label)),
+        FeedKind::Atom => Box::new(AtomSource::new(url, label)),
+        FeedKind::Rdf => Box::new(RdfSource::new(url, label)),
+        FeedKind::Json => Box::new(JsonFeedSource::new(url, label)),
+    }
+}
+
+/// The outcome of one successful [`DataSource::fetch`].
+///
+/// The default value — no items, no hints — is what an unchanged feed
+/// (HTTP 304) yields.
+#[derive(Debug, Default)]
+pub struct Fetched {
+    /// Items in the downloaded document.
+    pub items: Vec<FeedItem>,
+    /// Polling hints from the document, or `None` if nothing was
+    /// downloaded and the previous hints still apply.
+    pub hints: Option<FeedHints>,
+}
 
 /// Trait that every data source must implement.
 ///
 /// The polling loop calls [`fetch()`](DataSource::fetch) periodically on a
-/// background thread, so implementations must be [`Send`].
+/// background thread, so implementations must be [`Send`].  It passes in
+/// the shared [`HttpClient`], which remembers cache validators per URL.
 ///
 /// ## Implementing a new source
 ///
@@ -36,9 +96,15 @@ use anyhow::Result;
 /// impl DataSource for MySource {
 ///     fn name(&self) -> &str { "my-source" }
 ///
-///     fn fetch(&self) -> Result<Vec<FeedItem>> {
-///         // Perform HTTP / IO, then convert into FeedItem values.
-///         todo!()
+///     fn url(&self) -> &str { "https://example.com/feed" }
+///
+///     fn fetch(&self, http: &HttpClient) -> Result<Fetched> {
+///         // Download via `http`, then convert into FeedItem values.
+///         let fetched = http.fetch("https://example.com/feed", |body| {
+///             Ok(Fetched { items: parse(body)?, hints: Some(FeedHints::default()) })
+///         })?;
+///         // `None` means 304 Not Modified: nothing new.
+///         Ok(fetched.unwrap_or_default())
 ///     }
 /// }
 /// ```
@@ -46,10 +112,71 @@ pub trait DataSource: Send {
     /// Human-readable label shown in the status bar / alongside items.
     fn name(&self) -> &str;
 
+    /// Where the source is fetched from, shown in the source dashboard.
+    fn url(&self) -> &str;
+
     /// Fetch the latest batch of items.
     ///
-    /// Implementations should perform their own HTTP/IO work and return
-    /// parsed [`FeedItem`] values.  Errors are propagated to the UI as
-    /// status messages.
-    fn fetch(&self) -> Result<Vec<FeedItem>>;
+    /// Implementations should download through `http` and return parsed
+    /// [`FeedItem`] values along with any [`FeedHints`] the document
+    /// carries; an unchanged feed (HTTP 304) yields [`Fetched::default`].
+    /// Errors are propagated to the UI as status messages.
+    fn fetch(&self, http: &HttpClient) -> Result<Fetched>;
+}
+
+/// Return the name of the root element of an XML document, if any.
+///
+/// Skips a byte-order mark, the XML declaration, comments, processing
+/// instructions and the doctype.  Used by sources to detect when a URL
+/// serves a different feed format than expected (e.g. a site that moved
+/// from RSS to Atom without changing its feed URL).
+pub fn root_element(body: &[u8]) -> Option<&str> {
+    let mut rest = body.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(body);
+    loop {
+        let start = rest.iter().position(|b| !b.is_ascii_whitespace())?;
+        rest = rest[start..].strip_prefix(b"<")?;
+        match rest.first()? {
+            // <?xml ...?>, <!-- ... -->, <!DOCTYPE ...>
+            b'?' | b'!' => {
+                let terminator: &[u8] = if rest.starts_with(b"!--") { b"-->" } else { b">" };
+                let end = rest
+                    .windows(terminator.len())
+                    .position(|w| w == terminator)?;
+                rest = &rest[end + terminator.len()..];
+            }
+            _ => {
+                let end = rest
+                    .iter()
+                    .position(|b| b.is_ascii_whitespace() || *b == b'>' || *b == b'/')?;
+                return std::str::from_utf8(&rest[..end]).ok();
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_element_skips_prolog() {
+        let xml = b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<!-- generated -->\n<!DOCTYPE x>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">";
+        assert_eq!(root_element(xml), Some("feed"));
+    }
+
+    #[test]
+    fn root_element_handles_bare_and_prefixed_names() {
+        assert_eq!(root_element(b"<rss version=\"2.0\">"), Some("rss"));
+        assert_eq!(root_element(b"<rdf:RDF>"), Some("rdf:RDF"));
+    }
+
+    #[test]
+    fn root_element_rejects_non_xml() {
+        assert_eq!(root_element(b"{\"version\": 1}"), None);
+        assert_eq!(root_element(b""), None);
+    }
 }
@@ -0,0 +1,384 @@
+//! RSS 1.0 (RDF) feed source implementation.
+//!
+//! RSS 1.0 is an RDF/XML vocabulary: `<item>` elements are siblings of
+//! `<channel>` under an `<rdf:RDF>` root, each identified by its
+//! `rdf:about` URI, with dates in Dublin Core `<dc:date>` (W3C-DTF).  The
+//! [`rss`] crate only understands RSS 0.9x/2.0, so this module walks the
+//! document with [`quick_xml`] instead.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+use quick_xml::{NsReader, XmlVersion};
+
+use super::hints::{self, FeedHints};
+use super::{DataSource, FeedItem, Fetched, HttpClient};
+
+const RDF_NS: &[u8] = b"http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const RSS1_NS: &[u8] = b"http://purl.org/rss/1.0/";
+const RSS09_NS: &[u8] = b"http://my.netscape.com/rdf/simple/0.9/";
+const DC_NS: &[u8] = b"http://purl.org/dc/elements/1.1/";
+const CONTENT_NS: &[u8] = b"http://purl.org/rss/1.0/modules/content/";
+const SY_NS: &[u8] = b"http://purl.org/rss/1.0/modules/syndication/";
+
+/// An RSS 1.0 / RDF feed data source.
+pub struct RdfSource {
+    /// The feed URL to poll.
+    pub url: String,
+    /// A human-readable label shown in the UI next to each item.
+    pub label: String,
+}
+
+/// The item child elements we care about.
+#[derive(Clone, Copy)]
+enum Field {
+    Title,
+    Link,
+    Description,
+    DcTitle,
+    DcDescription,
+    DcDate,
+    DcCreator,
+    ContentEncoded,
+    /// `sy:updatePeriod` on the channel.
+    SyPeriod,
+    /// `sy:updateFrequency` on the channel.
+    SyFrequency,
+}
+
+/// Fields collected for the `<item>` currently being read.
+#[derive(Default)]
+struct PartialItem {
+    about: Option<String>,
+    title: Option<String>,
+    link: Option<String>,
+    description: Option<String>,
+    dc_title: Option<String>,
+    dc_description: Option<String>,
+    dc_date: Option<String>,
+    dc_creator: Option<String>,
+    content_encoded: Option<String>,
+}
+
+impl RdfSource {
+    /// Create a new RDF source.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` — full URL of the RSS 1.0 feed.
+    /// * `label` — short name displayed in the TUI for items from this feed.
+    pub fn new(url: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Parse an RSS 1.0 document into [`FeedItem`]s.
+    ///
+    /// There is no typed document to hand over as with
+    /// [`super::RssSource::parse_channel`], so this takes the raw bytes, but
+    /// it is still pure (no I/O).
+    ///
+    /// The channel's `sy:updatePeriod` / `sy:updateFrequency`, if present,
+    /// are returned as the polling hint.
+    pub fn parse_document(xml: &[u8], label: &str) -> Result<Fetched> {
+        let mut reader = NsReader::from_reader(xml);
+        let mut buf = Vec::new();
+        let mut items = Vec::new();
+        let mut current: Option<PartialItem> = None;
+        let mut field: Option<Field> = None;
+        let mut text = String::new();
+        let (mut sy_period, mut sy_frequency) = (None, None);
+
+        loop {
+            let (ns, event) = reader.read_resolved_event_into(&mut buf)?;
+            let ns = match ns {
+                ResolveResult::Bound(ns) => ns.into_inner(),
+                _ => b"",
+            };
+            match event {
+                Event::Start(e) if current.is_none() => {
+                    let is_item = e.local_name().as_ref() == b"item"
+                        && (ns == RSS1_NS || ns == RSS09_NS);
+                    if is_item {
+                        let mut item = PartialItem::default();
+                        for attr in e.attributes().flatten() {
+                            let (attr_ns, local) = reader.resolver().resolve_attribute(attr.key);
+                            if matches!(attr_ns, ResolveResult::Bound(n) if n.as_ref() == RDF_NS)
+                                && local.as_ref() == b"about"
+                            {
+                                item.about = Some(
+                                    attr.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())?
+                                        .into_owned(),
+                                );
+                            }
+                        }
+                        current = Some(item);
+                    } else if ns == SY_NS {
+                        field = match e.local_name().as_ref() {
+                            b"updatePeriod" => Some(Field::SyPeriod),
+                            b"updateFrequency" => Some(Field::SyFrequency),
+                            _ => None,
+                        };
+                        text.clear();
+                    }
+                }
+                Event::Start(e) => {
+                    field = match (ns, e.local_name().as_ref()) {
+                        (RSS1_NS | RSS09_NS, b"title") => Some(Field::Title),
+                        (RSS1_NS | RSS09_NS, b"link") => Some(Field::Link),
+                        (RSS1_NS | RSS09_NS, b"description") => Some(Field::Description),
+                        (DC_NS, b"title") => Some(Field::DcTitle),
+                        (DC_NS, b"description") => Some(Field::DcDescription),
+                        (DC_NS, b"date") => Some(Field::DcDate),
+                        (DC_NS, b"creator") => Some(Field::DcCreator),
+                        (CONTENT_NS, b"encoded") => Some(Field::ContentEncoded),
+                        _ => None,
+                    };
+                    text.clear();
+                }
+                Event::Text(t) if field.is_some() => text.push_str(&t.xml10_content()?),
+                Event::CData(t) if field.is_some() => text.push_str(&t.decode()?),
+                Event::GeneralRef(r) if field.is_some() => {
+                    if let Some(c) = r.resolve_char_ref()? {
+                        text.push(c);
+                    } else if let Some(s) =
+                        quick_xml::escape::resolve_predefined_entity(&r.decode()?)
+                    {
+                        text.push_str(s);
+                    }
+                }
+                Event::End(e) => {
+                    let value = Some(text.trim().to_string()).filter(|s| !s.is_empty());
+                    let Some(item) = current.as_mut() else {
+                        match field.take() {
+                            Some(Field::SyPeriod) => sy_period = value,
+                            Some(Field::SyFrequency) => sy_frequency = value,
+                            _ => {}
+                        }
+                        buf.clear();
+                        continue;
+                    };
+                    if let Some(f) = field.take() {
+                        let slot = match f {
+                            Field::Title => &mut item.title,
+                            Field::Link => &mut item.link,
+                            Field::Description => &mut item.description,
+                            Field::DcTitle => &mut item.dc_title,
+                            Field::DcDescription => &mut item.dc_description,
+                            Field::DcDate => &mut item.dc_date,
+                            Field::DcCreator => &mut item.dc_creator,
+                            Field::ContentEncoded => &mut item.content_encoded,
+                            Field::SyPeriod | Field::SyFrequency => continue,
+                        };
+                        *slot = value;
+                    } else if e.local_name().as_ref() == b"item" {
+                        if let Some(item) = current.take() {
+                            items.push(item.into_feed_item(label));
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        let min_interval = sy_period.and_then(|period| {
+            let frequency = sy_frequency.and_then(|f| f.parse().ok()).unwrap_or(1);
+            hints::update_interval(&period, frequency)
+        });
+        Ok(Fetched {
+            items,
+            hints: Some(FeedHints {
+                min_interval,
+                ..FeedHints::default()
+            }),
+        })
+    }
+}
+
+impl PartialItem {
+    fn into_feed_item(self, label: &str) -> FeedItem {
+        FeedItem {
+            // rdf:about is the item's URI; it is required but fall back to
+            // <link> for feeds that leave it out.
+            id: self.about.or_else(|| self.link.clone()).unwrap_or_default(),
+            title: self
+                .title
+                .or(self.dc_title)
+                .unwrap_or_else(|| "(untitled)".into()),
+            description: self.content_encoded.or(self.description).or(self.dc_description),
+            link: self.link,
+            published: self.dc_date.as_deref().and_then(parse_w3cdtf),
+            source_name: label.to_string(),
+            author: self.dc_creator,
+        }
+    }
+}
+
+/// Parse a W3C-DTF timestamp as used by `<dc:date>`.
+///
+/// W3C-DTF is a profile of ISO 8601 that, unlike RFC 3339, allows the
+/// seconds to be omitted and allows reduced precision (date only).
+fn parse_w3cdtf(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    // hh:mm without seconds; normalise a trailing "Z" to an offset.
+    let with_offset = s.strip_suffix('Z').map(|p| format!("{p}+00:00"));
+    if let Ok(dt) = DateTime::parse_from_str(with_offset.as_deref().unwrap_or(s), "%Y-%m-%dT%H:%M%:z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{s}-01"), "%Y-%m-%d"))
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{s}-01-01"), "%Y-%m-%d"))
+        .ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+impl DataSource for RdfSource {
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch(&self, http: &HttpClient) -> Result<Fetched> {
+        let fetched = http.fetch(&self.url, |body| Self::parse_document(body, &self.label))?;
+        Ok(fetched.unwrap_or_default())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rdf:RDF
+  xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+  xmlns="http://purl.org/rss/1.0/"
+  xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel rdf:about="https://example.org/">
+    <title>Channel title is not an item</title>
+    <link>https://example.org/</link>
+    <items>
+      <rdf:Seq>
+        <rdf:li rdf:resource="https://example.org/1"/>
+        <rdf:li rdf:resource="https://example.org/2"/>
+      </rdf:Seq>
+    </items>
+  </channel>
+  <item rdf:about="https://example.org/1">
+    <title>Tom &amp; Jerry&#x27;s paper</title>
+    <link>https://example.org/1.html</link>
+    <description><![CDATA[<p>Abstract</p>]]></description>
+    <dc:date>2024-01-01T12:30:00Z</dc:date>
+    <dc:creator>Ada</dc:creator>
+  </item>
+  <item rdf:about="https://example.org/2">
+    <dc:title>Only a DC title</dc:title>
+    <link>https://example.org/2.html</link>
+    <dc:date>2024-01-02</dc:date>
+  </item>
+</rdf:RDF>"#;
+
+    #[test]
+    fn parse_document_extracts_items() {
+        let items = RdfSource::parse_document(FEED.as_bytes(), "TestFeed").unwrap().items;
+
+        assert_eq!(items.len(), 2, "channel must not be counted as an item");
+
+        assert_eq!(items[0].id, "https://example.org/1");
+        assert_eq!(items[0].title, "Tom & Jerry's paper");
+        assert_eq!(items[0].link.as_deref(), Some("https://example.org/1.html"));
+        assert_eq!(items[0].description.as_deref(), Some("<p>Abstract</p>"));
+        assert_eq!(items[0].author.as_deref(), Some("Ada"));
+        assert_eq!(items[0].source_name, "TestFeed");
+        assert_eq!(
+            items[0].published,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap())
+        );
+
+        assert_eq!(items[1].title, "Only a DC title");
+        assert_eq!(
+            items[1].published,
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()),
+            "date-only dc:date means midnight UTC"
+        );
+        assert!(items[1].description.is_none());
+    }
+
+    #[test]
+    fn reads_syndication_hint() {
+        let xml = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+  xmlns="http://purl.org/rss/1.0/" xmlns:sy="http://purl.org/rss/1.0/modules/syndication/">
+  <channel rdf:about="x">
+    <sy:updatePeriod>daily</sy:updatePeriod>
+    <sy:updateFrequency>2</sy:updateFrequency>
+  </channel>
+  <item rdf:about="a"><title>A</title></item>
+</rdf:RDF>"#;
+        let fetched = RdfSource::parse_document(xml.as_bytes(), "t").unwrap();
+        assert_eq!(fetched.items.len(), 1);
+        assert_eq!(
+            fetched.hints.unwrap().min_interval,
+            Some(std::time::Duration::from_secs(12 * 3600))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_link_when_no_about() {
+        let xml = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+  xmlns="http://purl.org/rss/1.0/">
+  <item><title>No about</title><link>https://example.org/x</link></item>
+</rdf:RDF>"#;
+        let items = RdfSource::parse_document(xml.as_bytes(), "t").unwrap().items;
+        assert_eq!(items[0].id, "https://example.org/x");
+        assert!(items[0].published.is_none());
+    }
+
+    #[test]
+    fn handles_missing_title() {
+        let xml = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+  xmlns="http://purl.org/rss/1.0/">
+  <item rdf:about="b"></item>
+</rdf:RDF>"#;
+        let items = RdfSource::parse_document(xml.as_bytes(), "t").unwrap().items;
+        assert_eq!(items[0].title, "(untitled)");
+    }
+
+    #[test]
+    fn rejects_malformed_xml() {
+        let xml = "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"><item></rdf:RDF>";
+        assert!(RdfSource::parse_document(xml.as_bytes(), "t").is_err());
+    }
+
+    #[test]
+    fn parses_w3cdtf_variants() {
+        let noon = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(parse_w3cdtf("2024-03-01T12:00:00Z"), Some(noon));
+        assert_eq!(parse_w3cdtf("2024-03-01T13:00+01:00"), Some(noon));
+        assert_eq!(parse_w3cdtf("2024-03-01T12:00Z"), Some(noon));
+        assert_eq!(
+            parse_w3cdtf("2024-03"),
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(parse_w3cdtf("last tuesday"), None);
+    }
+
+    #[test]
+    fn name_returns_label() {
+        let src = RdfSource::new("http://example.com/index.rdf", "My Feed");
+        assert_eq!(src.name(), "My Feed");
+    }
+}
@@ -10,20 +10,26 @@
 //! 2. Define a struct that holds any configuration your source needs (URL,
 //!    API key, etc.).
 //! 3. Implement [`DataSource`] for your struct — `name()` returns a label and
-//!    `fetch()` returns `Vec<FeedItem>`.
+//!    `fetch(&self, http: &HttpClient)` downloads through
+//!    [`HttpClient::fetch`] and returns a [`Result<Fetched>`](Fetched).
 //! 4. Re-export your struct from `src/source/mod.rs`.
-//! 5. Wire it into the source list in `main.rs`.
+//! 5. Add a [`super::FeedKind`] variant for it and construct it in
+//!    [`super::from_config`].
 //!
 //! The RSS implementation below is a complete worked example.
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 
-use super::{DataSource, FeedItem};
+use super::json_feed::JsonFeed;
+use super::{AtomSource, DataSource, FeedHints, FeedItem, Fetched, HttpClient, JsonFeedSource, RdfSource};
 
 /// An RSS feed data source.
 ///
 /// Fetches and parses an RSS 2.0 feed over HTTP using the [`rss`] crate.
+/// If the URL turns out to serve an Atom document, an RSS 1.0 (RDF) document
+/// or a JSON Feed instead, it is handed to the matching parser so a single
+/// URL works for any format.
 pub struct RssSource {
     /// The feed URL to poll.
     pub url: String,
@@ -46,6 +52,33 @@ impl RssSource {
         }
     }
 
+    /// Parse a downloaded document of whichever format it turns out to be.
+    fn parse_any(body: &[u8], label: &str) -> Result<Fetched> {
+        let no_hints = |items| Fetched {
+            items,
+            hints: Some(FeedHints::default()),
+        };
+        if body.trim_ascii_start().starts_with(b"{") {
+            let feed: JsonFeed = serde_json::from_slice(body)?;
+            return Ok(no_hints(JsonFeedSource::parse_feed(&feed, label)));
+        }
+        match super::root_element(body) {
+            Some("feed") => {
+                let feed = atom_syndication::Feed::read_from(body)?;
+                return Ok(no_hints(AtomSource::parse_feed(&feed, label)));
+            }
+            Some(root) if root.rsplit(':').next() == Some("RDF") => {
+                return RdfSource::parse_document(body, label);
+            }
+            _ => {}
+        }
+        let channel = rss::Channel::read_from(body)?;
+        Ok(Fetched {
+            items: Self::parse_channel(&channel, label),
+            hints: Some(FeedHints::from_channel(&channel)),
+        })
+    }
+
     /// Parse an already-fetched [`rss::Channel`] into [`FeedItem`]s.
     ///
     /// This is a pure function (no I/O) so that tests can exercise the
@@ -75,6 +108,14 @@ impl RssSource {
                     link: item.link().map(String::from),
                     published,
                     source_name: label.to_string(),
+                    // <author> is an e-mail address in RSS 2.0; most feeds
+                    // put the display name in <dc:creator> instead.
+                    author: item
+                        .dublin_core_ext()
+                        .and_then(|dc| dc.creators().first())
+                        .map(String::as_str)
+                        .or(item.author())
+                        .map(String::from),
                 }
             })
             .collect()
@@ -86,10 +127,13 @@ impl DataSource for RssSource {
         &self.label
     }
 
-    fn fetch(&self) -> Result<Vec<FeedItem>> {
-        let body = reqwest::blocking::get(&self.url)?.bytes()?;
-        let channel = rss::Channel::read_from(&body[..])?;
-        Ok(Self::parse_channel(&channel, &self.label))
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch(&self, http: &HttpClient) -> Result<Fetched> {
+        let fetched = http.fetch(&self.url, |body| Self::parse_any(body, &self.label))?;
+        Ok(fetched.unwrap_or_default())
     }
 }
 
@@ -140,6 +184,31 @@ mod tests {
         assert!(items[1].description.is_none());
     }
 
+    #[test]
+    fn parse_channel_extracts_author() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Test</title>
+    <item>
+      <guid>g1</guid>
+      <author>ada@example.com (Ada)</author>
+    </item>
+    <item>
+      <guid>g2</guid>
+      <author>grace@example.com</author>
+      <dc:creator>Grace</dc:creator>
+    </item>
+  </channel>
+</rss>"#;
+
+        let channel = rss::Channel::read_from(xml.as_bytes()).unwrap();
+        let items = RssSource::parse_channel(&channel, "t");
+
+        assert_eq!(items[0].author.as_deref(), Some("ada@example.com (Ada)"));
+        assert_eq!(items[1].author.as_deref(), Some("Grace"), "dc:creator wins over author");
+    }
+
     #[test]
     fn falls_back_to_link_when_no_guid() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -197,6 +266,31 @@ mod tests {
         assert!(items[0].published.is_none());
     }
 
+    #[test]
+    fn fetch_sniffs_format_and_skips_unchanged_feed() {
+        use crate::testutil::{Response, Server};
+
+        let server = Server::start(|req| match req.header("if-none-match") {
+            Some("\"a1\"") => Response::status(304),
+            _ => Response::ok(r#"{"version": "https://jsonfeed.org/version/1.1", "title": "t",
+                "items": [{"id": "1", "title": "From JSON"}]}"#)
+                .header("ETag", "\"a1\""),
+        });
+        let src = RssSource::new(server.url("/feed"), "Test");
+        let http = HttpClient::new();
+
+        let fetched = src.fetch(&http).unwrap();
+        assert_eq!(fetched.items[0].title, "From JSON");
+        assert!(fetched.hints.is_some());
+        let fetched = src.fetch(&http).unwrap();
+        assert!(fetched.items.is_empty(), "304 yields no items");
+        assert!(fetched.hints.is_none(), "and keeps the previous hints");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!((requests[1].method.as_str(), requests[1].path.as_str()), ("GET", "/feed"));
+    }
+
     #[test]
     fn name_returns_label() {
         let src = RssSource::new("http://example.com/feed", "My Feed");
@@ -0,0 +1,324 @@
+//! On-disk item history.
+//!
+//! [`Store`] is an append-only JSON Lines file (one [`Record`] per line)
+//! under the XDG data directory, by default
+//! `~/.local/share/livescroll-rss/history.jsonl`.  Every item accepted by
+//! [`crate::app::App::merge_items`] is appended, as is every read/unread
+//! change, and the file is replayed into the `App` at startup so history
+//! and read state survive restarts.
+//!
+//! ## For contributors
+//!
+//! * Appends are the only writes during a session.  Items older than the
+//!   retention window (and the read state of those items) are dropped when
+//!   the store is opened, by rewriting the file to a temporary sibling and
+//!   renaming it over the original.
+//! * Unparseable lines (e.g. a half-written line after a crash, or one
+//!   that is not UTF-8) are skipped rather than treated as fatal, and
+//!   removed by the same compaction so the next append starts on a fresh
+//!   line.  Errors reading the file are fatal.
+//! * To persist a new kind of state, add a [`Record`] variant; old files
+//!   simply don't contain it.
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::source::FeedItem;
+
+/// One line of the history file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Record {
+    /// An item was merged into the list for the first time.
+    Item {
+        /// When it was first seen; the retention window counts from here.
+        seen_at: DateTime<Utc>,
+        item: FeedItem,
+    },
+    /// The user read the item with this id.
+    Read { id: String },
+    /// The user marked the item with this id unread again.
+    Unread { id: String },
+}
+
+/// The contents of a history file after replay.
+#[derive(Debug, Default)]
+pub struct History {
+    /// Retained items, oldest first.
+    pub items: Vec<FeedItem>,
+    /// IDs of retained items that are marked read.
+    pub read: HashSet<String>,
+}
+
+/// An open history file.
+pub struct Store {
+    file: File,
+}
+
+impl Store {
+    /// Open (creating if needed) the history file at `path`.
+    ///
+    /// Items older than `retention` are discarded and the file compacted.
+    /// Returns the store together with the replayed [`History`].
+    pub fn open(path: &Path, retention: Option<Duration>) -> Result<(Self, History)> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+
+        let (records, corrupt) = match File::open(path) {
+            Ok(f) => read_records(f).with_context(|| format!("reading {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Vec::new(), false),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+
+        let mut items = Vec::new();
+        let mut read = HashSet::new();
+        for record in records {
+            match record {
+                Record::Item { seen_at, item } => items.push((seen_at, item)),
+                Record::Read { id } => {
+                    read.insert(id);
+                }
+                Record::Unread { id } => {
+                    read.remove(&id);
+                }
+            }
+        }
+
+        let total = items.len();
+        if let Some(cutoff) = retention.map(|r| Utc::now() - r) {
+            items.retain(|(seen_at, _)| *seen_at >= cutoff);
+            let ids: HashSet<&str> = items.iter().map(|(_, i)| i.id.as_str()).collect();
+            read.retain(|id| ids.contains(id.as_str()));
+        }
+        if corrupt || items.len() != total {
+            let records = items
+                .iter()
+                .map(|(seen_at, item)| Record::Item {
+                    seen_at: *seen_at,
+                    item: item.clone(),
+                })
+                .chain(read.iter().map(|id| Record::Read { id: id.clone() }));
+            rewrite(path, records).with_context(|| format!("compacting {}", path.display()))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening {}", path.display()))?;
+
+        let history = History {
+            items: items.into_iter().map(|(_, item)| item).collect(),
+            read,
+        };
+        Ok((Self { file }, history))
+    }
+
+    /// Append newly-seen items.
+    pub fn append(&mut self, items: &[FeedItem]) -> Result<()> {
+        let now = Utc::now();
+        let mut buf = Vec::new();
+        for item in items {
+            let record = Record::Item {
+                seen_at: now,
+                item: item.clone(),
+            };
+            serde_json::to_writer(&mut buf, &record)?;
+            buf.push(b'\n');
+        }
+        // One write per batch keeps lines from interleaving with a partial
+        // batch if we are killed mid-way.
+        self.file.write_all(&buf)?;
+        Ok(())
+    }
+
+    /// Record read-state changes as returned by
+    /// [`crate::app::App::take_read_changes`].
+    pub fn set_read(&mut self, changes: &[(String, bool)]) -> Result<()> {
+        let mut buf = Vec::new();
+        for (id, read) in changes {
+            let id = id.clone();
+            let record = if *read { Record::Read { id } } else { Record::Unread { id } };
+            serde_json::to_writer(&mut buf, &record)?;
+            buf.push(b'\n');
+        }
+        self.file.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// `$XDG_DATA_HOME/livescroll-rss/history.jsonl` (or the platform equivalent).
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("livescroll-rss").join("history.jsonl"))
+}
+
+/// Read every parseable record, and whether any line had to be skipped.
+/// Lines that are not UTF-8 are skipped like any other bad line.
+fn read_records(file: File) -> std::io::Result<(Vec<Record>, bool)> {
+    let mut corrupt = false;
+    let mut records = Vec::new();
+    for line in BufReader::new(file).split(b'\n') {
+        let record = serde_json::from_slice(&line?).ok();
+        corrupt |= record.is_none();
+        records.extend(record);
+    }
+    Ok((records, corrupt))
+}
+
+fn rewrite(path: &Path, records: impl Iterator<Item = Record>) -> Result<()> {
+    let tmp = path.with_extension("jsonl.tmp");
+    let mut out = BufWriter::new(File::create(&tmp)?);
+    for record in records {
+        serde_json::to_writer(&mut out, &record)?;
+        out.write_all(b"\n")?;
+    }
+    out.into_inner()?.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil;
+    use chrono::TimeZone;
+
+    fn make_item(id: &str) -> FeedItem {
+        let published = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        FeedItem {
+            description: Some("<p>body</p>".into()),
+            link: Some(format!("https://example.com/{id}")),
+            ..testutil::make_item(id, &format!("Title {id}"), Some(published))
+        }
+    }
+
+    #[test]
+    fn appended_items_are_loaded_on_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("history.jsonl");
+
+        let (mut store, history) = Store::open(&path, None).unwrap();
+        assert!(history.items.is_empty());
+        store.append(&[make_item("a"), make_item("b")]).unwrap();
+        store.append(&[make_item("c")]).unwrap();
+        drop(store);
+
+        let (_, History { items, .. }) = Store::open(&path, None).unwrap();
+        let ids: Vec<_> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(items[0], make_item("a"), "all fields survive the round trip");
+    }
+
+    #[test]
+    fn retention_drops_old_records_and_compacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        let old = Record::Item {
+            seen_at: Utc::now() - Duration::days(40),
+            item: make_item("old"),
+        };
+        let line = serde_json::to_string(&old).unwrap();
+        fs::write(&path, format!("{line}\n")).unwrap();
+        let (mut store, _) = Store::open(&path, None).unwrap();
+        store.append(&[make_item("new")]).unwrap();
+        drop(store);
+
+        let (_, History { items, .. }) = Store::open(&path, Some(Duration::days(30))).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "new");
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 1, "old record removed from disk");
+    }
+
+    #[test]
+    fn read_state_is_replayed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        let (mut store, _) = Store::open(&path, None).unwrap();
+        store.append(&[make_item("a"), make_item("b")]).unwrap();
+        store
+            .set_read(&[("a".into(), true), ("b".into(), true), ("b".into(), false)])
+            .unwrap();
+        drop(store);
+
+        let (_, history) = Store::open(&path, None).unwrap();
+        assert_eq!(history.read, HashSet::from(["a".to_string()]));
+    }
+
+    #[test]
+    fn retention_drops_read_state_of_expired_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        let old = Record::Item {
+            seen_at: Utc::now() - Duration::days(40),
+            item: make_item("old"),
+        };
+        fs::write(&path, format!("{}\n", serde_json::to_string(&old).unwrap())).unwrap();
+        let (mut store, _) = Store::open(&path, None).unwrap();
+        store.append(&[make_item("new")]).unwrap();
+        store.set_read(&[("old".into(), true), ("new".into(), true)]).unwrap();
+        drop(store);
+
+        let (_, history) = Store::open(&path, Some(Duration::days(30))).unwrap();
+        assert_eq!(history.read, HashSet::from(["new".to_string()]));
+
+        let (_, history) = Store::open(&path, None).unwrap();
+        assert_eq!(history.read.len(), 1, "compaction kept the surviving read record");
+    }
+
+    #[test]
+    fn skips_and_removes_corrupt_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        let (mut store, _) = Store::open(&path, None).unwrap();
+        store.append(&[make_item("a")]).unwrap();
+        drop(store);
+        let mut f = OpenOptions::new().append(true).open(&path).unwrap();
+        f.write_all(b"{\"kind\":\"item\",\"seen_at\":").unwrap();
+        drop(f);
+
+        let (mut store, history) = Store::open(&path, None).unwrap();
+        assert_eq!(history.items.len(), 1);
+        store.append(&[make_item("b")]).unwrap();
+        drop(store);
+
+        let (_, history) = Store::open(&path, None).unwrap();
+        assert_eq!(history.items.len(), 2, "append after a torn line is not lost");
+    }
+
+    #[test]
+    fn skips_lines_that_are_not_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        let (mut store, _) = Store::open(&path, None).unwrap();
+        store.append(&[make_item("a")]).unwrap();
+        drop(store);
+        let mut f = OpenOptions::new().append(true).open(&path).unwrap();
+        f.write_all(b"{\"kind\":\"read\",\"id\":\"\xff\"}\n").unwrap();
+        drop(f);
+        let (mut store, _) = Store::open(&path, None).unwrap();
+        store.append(&[make_item("b")]).unwrap();
+        drop(store);
+
+        let (_, history) = Store::open(&path, None).unwrap();
+        assert_eq!(history.items.len(), 2, "records after the bad line are kept");
+        assert!(!fs::read(&path).unwrap().contains(&0xff), "bad line compacted away");
+    }
+}
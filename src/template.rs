@@ -0,0 +1,116 @@
+//! `{placeholder}` templates filled in from a [`FeedItem`].
+//!
+//! Used for `--headless` output lines and webhook bodies.  Placeholders:
+//! `{title}`, `{link}`, `{source}`, `{published}` (RFC 3339), `{author}`,
+//! `{id}` and `{description}` (as plain text).  Each value has its runs of
+//! whitespace collapsed to one space, so it fits on one line even if the
+//! feed wraps it.  Missing values are empty; unknown placeholders are left
+//! as they are.
+//!
+//! ## For contributors
+//!
+//! * To add a placeholder, add an arm to [`value`] and list it in the
+//!   `--template` help text in [`crate::cli`] and in the docs.
+
+use crate::source::FeedItem;
+
+/// Fill in `template` from `item`.
+pub fn fill(template: &str, item: &FeedItem) -> String {
+    expand(template, item, str::to_string)
+}
+
+/// Fill in `template` from `item` with each value escaped as the inside
+/// of a JSON string, so that `{"text": "{title}"}` stays valid JSON.
+pub fn fill_json(template: &str, item: &FeedItem) -> String {
+    expand(template, item, |value| {
+        let quoted = serde_json::to_string(value).expect("strings serialise");
+        quoted[1..quoted.len() - 1].to_string()
+    })
+}
+
+fn expand(template: &str, item: &FeedItem, escape: impl Fn(&str) -> String) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        match value(&rest[1..end], item) {
+            Some(value) => {
+                out.push_str(&escape(&value));
+                rest = &rest[end + 1..];
+            }
+            // Not a placeholder, e.g. the `{` opening a JSON object: look
+            // for one after it.
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The value of placeholder `name` on one line, or `None` if there is no
+/// such placeholder.
+fn value(name: &str, item: &FeedItem) -> Option<String> {
+    let value = match name {
+        "title" => item.title.clone(),
+        "link" => item.link.clone().unwrap_or_default(),
+        "source" => item.source_name.clone(),
+        "published" => item.published.map(|d| d.to_rfc3339()).unwrap_or_default(),
+        "author" => item.author.clone().unwrap_or_default(),
+        "id" => item.id.clone(),
+        "description" => item.description.as_deref().map(crate::html::to_plain).unwrap_or_default(),
+        _ => return None,
+    };
+    Some(value.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::make_item;
+    use chrono::{TimeZone, Utc};
+
+    fn item() -> FeedItem {
+        let published = Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap();
+        FeedItem {
+            description: Some("<p>Some <b>bold</b>\n text</p>".into()),
+            link: Some("https://example.com/1".into()),
+            source_name: "Example".into(),
+            ..make_item("1", "Say \"hi\"", Some(published))
+        }
+    }
+
+    #[test]
+    fn fills_in_placeholders() {
+        assert_eq!(
+            fill("{published} [{source}] {title} {link}", &item()),
+            "2025-01-01T09:00:00+00:00 [Example] Say \"hi\" https://example.com/1"
+        );
+        assert_eq!(
+            fill("{id}\t{author}|{description}|{nope} {", &item()),
+            "1\t|Some bold text|{nope} {"
+        );
+        let wrapped = FeedItem {
+            title: "A title\n  wrapped\tacross lines ".into(),
+            ..item()
+        };
+        assert_eq!(fill("{title}|", &wrapped), "A title wrapped across lines|");
+    }
+
+    #[test]
+    fn json_values_are_escaped() {
+        let body = fill_json("{\"text\": \"{title}\\n{link}\"}", &item());
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["text"], "Say \"hi\"\nhttps://example.com/1");
+    }
+}
@@ -6,8 +6,13 @@
 //!
 //! ## For contributors
 //!
-//! * The layout is a two-row split: a scrollable list on top and a one-line
+//! * The layout is a vertical split: a scrollable list on top, the preview
+//!   pane for the selected item (when enabled) below it, and a one-line
 //!   status bar at the bottom.
+//! * HTML in descriptions is converted to styled lines by [`crate::html`].
+//! * In [`View::Sources`] the list and preview are replaced by a table of
+//!   sources and their fetch health, and in [`View::Rules`] by the filter
+//!   rules.
 //! * Colours and styles are defined inline — feel free to extract them into
 //!   constants or a theme struct if the palette grows.
 //! * [`ratatui`] is the TUI framework; see its docs for widget details.
@@ -16,25 +21,59 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Cell, List, ListItem, Padding, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
-use crate::app::App;
+use chrono::{DateTime, Local, Utc};
+
+use crate::app::{App, View};
+use crate::filter::Action;
+use crate::search::Search;
 
 /// Draw the complete UI for one frame.
 ///
 /// Called once per tick from the main loop.  Delegates to helper functions
 /// for each screen region.
 pub fn draw(app: &mut App, frame: &mut Frame) {
+    let (main_area, preview_area, status_area) = areas(app, frame.area());
+    match app.view {
+        View::Sources => draw_sources(app, frame, main_area),
+        View::Rules => draw_rules(app, frame, main_area),
+        View::Items => {
+            draw_feed_list(app, frame, main_area);
+            if let Some(area) = preview_area {
+                draw_preview(app, frame, area);
+            }
+        }
+    }
+    draw_status_bar(app, frame, status_area);
+}
+
+/// Split the terminal `area` into the main area (the list, when there is
+/// a preview pane), the preview pane if shown, and the status bar.
+fn areas(app: &App, area: Rect) -> (Rect, Option<Rect>, Rect) {
     let [main_area, status_area] = Layout::vertical([
         Constraint::Min(1),
         Constraint::Length(1),
     ])
-    .areas(frame.area());
+    .areas(area);
+    if app.view != View::Items || !app.show_preview {
+        return (main_area, None, status_area);
+    }
+    let [list_area, preview_area] = Layout::vertical([
+        Constraint::Percentage(40),
+        Constraint::Percentage(60),
+    ])
+    .areas(main_area);
+    (list_area, Some(preview_area), status_area)
+}
 
-    draw_feed_list(app, frame, main_area);
-    draw_status_bar(app, frame, status_area);
+/// How many lines the preview of the selected item takes once wrapped,
+/// on a terminal of size `area`.  `None` if no preview is shown.
+pub fn preview_line_count(app: &mut App, area: Rect) -> Option<usize> {
+    let inner = preview_block().inner(areas(app, area).1?);
+    Some(preview_text(app, inner.width)?.line_count(inner.width))
 }
 
 /// Render the scrollable feed item list.
@@ -43,24 +82,40 @@ fn draw_feed_list(app: &mut App, frame: &mut Frame, area: Rect) {
         .items
         .iter()
         .map(|item| {
+            let unread = !app.is_read(item);
+            let verdict = app.verdict(item);
             let date_str = item
                 .published
                 .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
                 .unwrap_or_else(|| "no date".into());
 
-            let line = Line::from(vec![
+            let title_style = if verdict.mute {
+                Style::default().fg(Color::DarkGray)
+            } else if unread {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            let title_style = match verdict.color {
+                Some(color) => title_style.fg(color),
+                None => title_style,
+            };
+
+            let mut spans = vec![
                 Span::styled(
                     format!("{:<18}", date_str),
                     Style::default().fg(Color::DarkGray),
                 ),
-                Span::raw(" "),
-                Span::styled(&item.title, Style::default().fg(Color::White)),
-                Span::raw("  "),
-                Span::styled(
-                    format!("[{}]", item.source_name),
-                    Style::default().fg(Color::Cyan),
-                ),
-            ]);
+                Span::raw(if unread { "●" } else { " " }),
+                Span::styled(if verdict.pin { "⚑ " } else { "" }, Style::default().fg(Color::Magenta)),
+            ];
+            spans.extend(highlight(&item.title, title_style, app.search.as_ref()));
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                format!("[{}]", item.source_name),
+                Style::default().fg(Color::Cyan),
+            ));
+            let line = Line::from(spans);
 
             ListItem::new(line)
         })
@@ -82,17 +137,315 @@ fn draw_feed_list(app: &mut App, frame: &mut Frame, area: Rect) {
     frame.render_stateful_widget(list, area, &mut app.list_state);
 }
 
-/// Render the bottom status bar.
+/// Split `text` into spans in `style`, with the hits of `search` picked
+/// out.  Hits are reversed rather than given a background so that they
+/// still show on the selected row.
+fn highlight<'a>(text: &'a str, style: Style, search: Option<&Search>) -> Vec<Span<'a>> {
+    let ranges = search.map(|s| s.ranges(text)).unwrap_or_default();
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for range in ranges {
+        if range.start > pos {
+            spans.push(Span::styled(&text[pos..range.start], style));
+        }
+        spans.push(Span::styled(&text[range.clone()], style.fg(Color::Yellow).add_modifier(Modifier::REVERSED)));
+        pos = range.end;
+    }
+    if pos < text.len() || spans.is_empty() {
+        spans.push(Span::styled(&text[pos..], style));
+    }
+    spans
+}
+
+/// Render the selected item's title, metadata and description.
+fn draw_preview(app: &mut App, frame: &mut Frame, area: Rect) {
+    let block = preview_block();
+    let inner = block.inner(area);
+    match preview_text(app, inner.width) {
+        Some(preview) => {
+            let preview = preview
+                .block(block)
+                .scroll((app.preview_scroll, 0));
+            frame.render_widget(preview, area);
+        }
+        None => frame.render_widget(block, area),
+    }
+}
+
+fn preview_block() -> Block<'static> {
+    Block::default()
+        .title(" Preview ")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+}
+
+/// The preview of the selected item for a pane `width` columns wide
+/// inside its border: title, source line and link, wrapped, then the
+/// description.  The description is converted once per item and width and
+/// kept in [`App::preview_lines`].
+fn preview_text(app: &mut App, width: u16) -> Option<Paragraph<'static>> {
+    let item = app.selected_item()?;
+    if !matches!(&app.preview_lines, Some((id, w, _)) if *id == item.id && *w == width) {
+        let description = match &item.description {
+            Some(html) => crate::html::to_lines(html, width.into()),
+            None => vec![Line::styled("(no description)", Style::default().fg(Color::DarkGray))],
+        };
+        app.preview_lines = Some((item.id.clone(), width, description));
+    }
+    let item = app.selected_item()?;
+    let description = app.preview_lines.as_ref().map(|(_, _, lines)| lines.clone()).unwrap_or_default();
+
+    let mut meta = vec![Span::styled(
+        item.source_name.clone(),
+        Style::default().fg(Color::Cyan),
+    )];
+    if let Some(author) = &item.author {
+        meta.push(Span::raw(format!("  {author}")));
+    }
+    if let Some(date) = item.published {
+        meta.push(Span::styled(
+            format!("  {}", date.format("%Y-%m-%d %H:%M")),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let mut lines = vec![
+        Line::styled(item.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        Line::from(meta),
+    ];
+    if let Some(link) = &item.link {
+        lines.push(Line::styled(link.clone(), Style::default().fg(Color::Blue)));
+    }
+    lines.push(Line::default());
+    lines.extend(description);
+    Some(Paragraph::new(lines).wrap(Wrap { trim: false }))
+}
+
+/// Render the source health dashboard.
+fn draw_sources(app: &App, frame: &mut Frame, area: Rect) {
+    let time = |t: Option<DateTime<Utc>>| {
+        t.map(|t| t.with_timezone(&Local).format("%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "—".into())
+    };
+
+    let rows = app.sources.iter().map(|s| {
+        let failing = s.health.consecutive_failures > 0;
+        let error = match (&s.health.last_error, failing) {
+            (Some(e), true) => format!("{e} ({}×)", s.health.consecutive_failures),
+            (Some(e), false) => format!("{e} (recovered)"),
+            (None, _) => String::new(),
+        };
+        Row::new(vec![
+            Cell::from(s.name.clone()).style(Style::default().fg(Color::Cyan)),
+            Cell::from(s.url.clone()),
+            Cell::from(time(s.last_fetch)),
+            Cell::from(s.http_status.map(|c| c.to_string()).unwrap_or_else(|| "—".into())),
+            Cell::from(app.item_count(&s.url).to_string()),
+            Cell::from(time(s.next_poll)),
+            Cell::from(error).style(Style::default().fg(if failing { Color::Red } else { Color::DarkGray })),
+        ])
+    });
+
+    let header = Row::new(["Source", "URL", "Last fetch", "HTTP", "Items", "Next poll", "Last error"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Fill(2),
+            Constraint::Length(14),
+            Constraint::Length(4),
+            Constraint::Length(5),
+            Constraint::Length(14),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .block(Block::default().title(" Sources ").borders(Borders::ALL));
+    frame.render_widget(table, area);
+}
+
+/// Render the filter rules, one per line in their text form.
+fn draw_rules(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = app
+        .rules()
+        .iter()
+        .map(|rule| {
+            let style = match rule.action {
+                Action::Highlight => Style::default().fg(
+                    rule.color.as_deref().and_then(|c| c.parse().ok()).unwrap_or(Color::Yellow),
+                ),
+                Action::Hide | Action::Mute => Style::default().fg(Color::Gray),
+                Action::Pin => Style::default().fg(Color::Magenta),
+                Action::Notify => Style::default().fg(Color::Cyan),
+            };
+            ListItem::new(Line::styled(rule.to_string(), style))
+        })
+        .collect();
+
+    let title = format!(
+        " Rules — {} items hidden  (a: add  d: delete  F: back) ",
+        app.hidden_count()
+    );
+    let list = List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(Color::DarkGray))
+        .highlight_symbol("▸ ");
+    frame.render_stateful_widget(list, area, &mut app.rules_state);
+}
+
+/// Render the bottom status bar, or the search / rule prompt while one is
+/// open.
 fn draw_status_bar(app: &App, frame: &mut Frame, area: Rect) {
+    if let Some(search) = app.search.as_ref().filter(|_| app.searching) {
+        draw_search_prompt(app, search, frame, area);
+        return;
+    }
+    if let Some(input) = &app.rule_input {
+        draw_rule_prompt(app, input, frame, area);
+        return;
+    }
+    if let Some(input) = &app.export_input {
+        draw_export_prompt(app, input, frame, area);
+        return;
+    }
+
+    let per_source = app
+        .unread_by_source()
+        .iter()
+        .map(|(source, n)| format!("{source} {n}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let unread = match app.unread_count() {
+        0 => "0 unread".to_string(),
+        n => format!("{n} unread ({per_source})"),
+    };
+
+    let failing = app.failing_sources();
+    let failing = match failing.as_slice() {
+        [] => String::new(),
+        [source] => format!(
+            "  ⚠ {}: {} ({} failures)",
+            source.name,
+            source.health.last_error.as_deref().unwrap_or("error"),
+            source.health.consecutive_failures
+        ),
+        many => format!(
+            "  ⚠ {} sources failing: {}",
+            many.len(),
+            many.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+    };
+
     let status = Paragraph::new(Line::from(vec![
         Span::styled(" ", Style::default()),
         Span::styled(&app.status, Style::default().fg(Color::Yellow)),
+        Span::styled(failing, Style::default().fg(Color::Red)),
         Span::raw("  "),
         Span::styled(
             format!("{} items", app.items.len()),
             Style::default().fg(Color::Green),
         ),
-        Span::raw("  q: quit  ↑↓/jk: scroll  Home/End/gG: jump"),
+        Span::raw("  "),
+        Span::styled(unread, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(
+            match app.hidden_count() {
+                0 => String::new(),
+                n => format!("  {n} hidden"),
+            },
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            if app.follow_newest { "  [follow]" } else { "" },
+            Style::default().fg(Color::Magenta),
+        ),
+        Span::styled(
+            if app.paused { "  [paused]" } else { "" },
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            match &app.search {
+                Some(search) => format!("  [/{}: {} matches]", search.query, app.match_count()),
+                None => String::new(),
+            },
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::raw("  q: quit  ↑↓/jk: scroll  Home/End/gG: jump  u: next unread  m/M: read/all read  o/Enter: open  J/K/PgUp/PgDn: preview  v: hide preview  f: follow newest  s: sources  r/R: refresh all/source  p: pause  /: search  n/N: next/prev hit  F: rules  E: export"),
     ]));
     frame.render_widget(status, area);
 }
+
+/// Render the "add rule" prompt in place of the status bar, with the
+/// last error (if any) and a reminder of the syntax.
+fn draw_rule_prompt(app: &App, input: &str, frame: &mut Frame, area: Rect) {
+    let prefix = " rule: ";
+    let hint = match &app.rule_error {
+        Some(error) => Span::styled(format!("  {error}"), Style::default().fg(Color::Red)),
+        None => Span::styled(
+            "  hide|highlight[:color]|pin|mute|notify [title:|description:|source:|link:]word or /regex/",
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    let prompt = Paragraph::new(Line::from(vec![
+        Span::styled(prefix, Style::default().fg(Color::Yellow)),
+        Span::raw(input),
+        hint,
+    ]));
+    frame.render_widget(prompt, area);
+
+    let width = (prefix.len() + input.chars().count()) as u16;
+    frame.set_cursor_position((area.x + width.min(area.width.saturating_sub(1)), area.y));
+}
+
+/// Render the "export to" prompt in place of the status bar, with the
+/// terminal cursor at the end of the file name.
+fn draw_export_prompt(app: &App, input: &str, frame: &mut Frame, area: Rect) {
+    let prefix = " export to: ";
+    let what = match &app.search {
+        Some(search) => format!("{} matches for {}", app.match_count(), search.query),
+        None => format!("{} items", app.items.len()),
+    };
+    let hint = match &app.export_error {
+        Some(error) => Span::styled(format!("  {error}"), Style::default().fg(Color::Red)),
+        None => Span::styled(
+            format!("  {what} as .md, .csv, .json or .html  Enter: export  Esc: cancel"),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    let prompt = Paragraph::new(Line::from(vec![
+        Span::styled(prefix, Style::default().fg(Color::Yellow)),
+        Span::raw(input),
+        hint,
+    ]));
+    frame.render_widget(prompt, area);
+
+    let width = (prefix.len() + input.chars().count()) as u16;
+    frame.set_cursor_position((area.x + width.min(area.width.saturating_sub(1)), area.y));
+}
+
+/// Render the search prompt in place of the status bar, with the
+/// terminal cursor at the end of the query.
+fn draw_search_prompt(app: &App, search: &Search, frame: &mut Frame, area: Rect) {
+    let prefix = if search.regex { " regex /" } else { " /" };
+    let feedback = match search.error() {
+        Some(error) => Span::styled(format!("  {error}"), Style::default().fg(Color::Red)),
+        None if search.query.is_empty() => Span::raw(""),
+        None => Span::styled(
+            format!("  {} matches", app.match_count()),
+            Style::default().fg(Color::Green),
+        ),
+    };
+    let prompt = Paragraph::new(Line::from(vec![
+        Span::styled(prefix, Style::default().fg(Color::Yellow)),
+        Span::raw(search.query.as_str()),
+        feedback,
+        Span::styled(
+            "  Enter: done  Esc: cancel  Ctrl-R: regex",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    frame.render_widget(prompt, area);
+
+    let width = (prefix.len() + search.query.chars().count()) as u16;
+    frame.set_cursor_position((area.x + width.min(area.width.saturating_sub(1)), area.y));
+}
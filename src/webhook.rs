@@ -0,0 +1,363 @@
+//! Webhooks: POST newly seen items as JSON to a URL.
+//!
+//! Each `[[webhook]]` table starts a [`Webhook`] with its own thread, so a
+//! slow or unreachable endpoint never holds up the UI.  New items are sent
+//! one request each, or all items from one fetch in one request with
+//! `batch = true`; optional `[[webhook.filter]]` tables limit which items
+//! are sent.
+//!
+//! ```toml
+//! [[webhook]]
+//! url = "https://chat.example.com/hooks/abc"
+//! template = '{"text": "{source}: {title} {link}"}'
+//!
+//! [[webhook.filter]]
+//! keyword = "rust"
+//! ```
+//!
+//! ## For contributors
+//!
+//! * Without a `template` the body is the item itself, serialised as in the
+//!   history file.  With one, values are JSON-escaped as they are filled
+//!   in (see [`crate::template::fill_json`]) and the result is checked to be
+//!   JSON at startup.  A batch is a JSON array of those bodies.
+//! * Network errors, 5xx, 408 and 429 responses are retried with
+//!   exponential backoff; other responses are not.  Failures are kept for
+//!   [`Webhook::take_errors`] rather than printed, as the TUI owns the
+//!   terminal.
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::config::WebhookConfig;
+use crate::filter::Filters;
+use crate::source::FeedItem;
+
+/// Wait before the first retry; doubled for each one after.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest time one request may take.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest time dropping a webhook waits for what is still queued.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// A running webhook.  Stops when dropped, after one last try at whatever
+/// is still queued, for at most [`SHUTDOWN_GRACE`].
+pub struct Webhook {
+    url: String,
+    filters: Filters,
+    items: Option<mpsc::Sender<Vec<FeedItem>>>,
+    /// Dropped to cut short any backoff in progress.
+    stop: Option<mpsc::Sender<()>>,
+    /// Disconnects when the thread has finished.
+    done: mpsc::Receiver<()>,
+    grace: Duration,
+    errors: Arc<Mutex<Vec<String>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Webhook {
+    /// Check `config` and start the webhook's thread.
+    pub fn start(config: &WebhookConfig) -> Result<Self> {
+        Self::spawn(config, RETRY_DELAY)
+    }
+
+    fn spawn(config: &WebhookConfig, retry_delay: Duration) -> Result<Self> {
+        reqwest::Url::parse(&config.url).context("invalid `url`")?;
+        let filters = Filters::new(&config.filters)?;
+        if let Some(template) = &config.template {
+            let sample = FeedItem {
+                id: "id".into(),
+                title: "A \"title\"".into(),
+                description: Some("<p>Text</p>".into()),
+                link: Some("https://example.com/".into()),
+                published: Some(chrono::Utc::now()),
+                source_name: "Source".into(),
+                author: Some("Author".into()),
+            };
+            serde_json::from_str::<serde_json::Value>(&crate::template::fill_json(template, &sample))
+                .context("`template` does not give valid JSON")?;
+        }
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let name = HeaderName::try_from(name).with_context(|| format!("invalid header name {name:?}"))?;
+            let value = HeaderValue::try_from(value).with_context(|| format!("invalid value for header {name}"))?;
+            headers.insert(name, value);
+        }
+        let client = Client::builder()
+            .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+            .timeout(TIMEOUT)
+            .default_headers(headers)
+            .build()?;
+
+        let (items, queue) = mpsc::channel::<Vec<FeedItem>>();
+        let (stop, stopped) = mpsc::channel();
+        let (finished, done) = mpsc::channel::<()>();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let delivery = Delivery {
+            client,
+            config: config.clone(),
+            retry_delay,
+            stopped,
+            errors: Arc::clone(&errors),
+        };
+        let thread = thread::Builder::new()
+            .name("webhook".into())
+            .spawn(move || {
+                let _finished = finished;
+                for items in queue {
+                    delivery.deliver(&items);
+                }
+            })?;
+
+        Ok(Self {
+            url: config.url.clone(),
+            filters,
+            items: Some(items),
+            stop: Some(stop),
+            done,
+            grace: SHUTDOWN_GRACE,
+            errors,
+            thread: Some(thread),
+        })
+    }
+
+    /// Queue the items in `items` that pass the filters for sending.
+    pub fn send(&self, items: &[FeedItem]) {
+        let items: Vec<FeedItem> = items.iter().filter(|i| self.filters.matches(i)).cloned().collect();
+        if let Some(queue) = self.items.as_ref().filter(|_| !items.is_empty()) {
+            let _ = queue.send(items);
+        }
+    }
+
+    /// Failures since the last call, each naming the URL.
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut *self.errors.lock().unwrap())
+            .into_iter()
+            .map(|e| format!("posting to {}: {e}", self.url))
+            .collect()
+    }
+}
+
+impl Drop for Webhook {
+    fn drop(&mut self) {
+        self.stop.take();
+        self.items.take();
+        // An unreachable endpoint could take `TIMEOUT` per queued request;
+        // past the grace period the thread is left to finish on its own.
+        if self.done.recv_timeout(self.grace) == Err(mpsc::RecvTimeoutError::Disconnected) {
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// The webhook thread's side.
+struct Delivery {
+    client: Client,
+    config: WebhookConfig,
+    retry_delay: Duration,
+    /// Disconnects when the [`Webhook`] is dropped.
+    stopped: mpsc::Receiver<()>,
+    errors: Arc<Mutex<Vec<String>>>,
+}
+
+/// Why a request failed, and whether trying again might help.
+struct Failure {
+    message: String,
+    retry: bool,
+}
+
+impl Delivery {
+    /// POST `items`, one by one or as a batch, recording failures.
+    fn deliver(&self, items: &[FeedItem]) {
+        let body = |item| match &self.config.template {
+            Some(template) => crate::template::fill_json(template, item),
+            None => serde_json::to_string(item).expect("items serialise"),
+        };
+        let bodies = if self.config.batch {
+            vec![format!("[{}]", items.iter().map(body).collect::<Vec<_>>().join(","))]
+        } else {
+            items.iter().map(body).collect()
+        };
+        for body in bodies {
+            if let Err(failure) = self.post_with_retries(&body) {
+                self.errors.lock().unwrap().push(failure.message);
+            }
+        }
+    }
+
+    fn post_with_retries(&self, body: &str) -> Result<(), Failure> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            match self.post(body) {
+                Err(failure) if failure.retry && attempt < self.config.retries => {
+                    // Give up at once when the webhook is being dropped.
+                    if self.stopped.recv_timeout(delay) != Err(mpsc::RecvTimeoutError::Timeout) {
+                        return Err(failure);
+                    }
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn post(&self, body: &str) -> Result<(), Failure> {
+        let request = self
+            .client
+            .post(&self.config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        let response = request.send().map_err(|e| Failure {
+            message: e.to_string(),
+            retry: true,
+        })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        Err(Failure {
+            message: format!("HTTP {status}"),
+            retry: status.is_server_error() || matches!(status.as_u16(), 408 | 429),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{make_item, Response, Server};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    fn item(title: &str) -> FeedItem {
+        FeedItem {
+            source_name: "Example".into(),
+            ..make_item(title, title, None)
+        }
+    }
+
+    fn config(url: String) -> WebhookConfig {
+        toml::from_str(&format!("url = {url:?}")).unwrap()
+    }
+
+    fn bodies(server: &Server) -> Vec<serde_json::Value> {
+        server
+            .requests()
+            .iter()
+            .map(|r| serde_json::from_slice(&r.body).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn posts_each_matching_item_with_template() {
+        let server = Server::start(|_| Response::status(204));
+        let mut config = config(server.url("/hook"));
+        config.template = Some(r#"{"text": "{source}: {title}"}"#.into());
+        config.headers.insert("Authorization".into(), "Bearer x".into());
+        config.filters = vec![toml::from_str("keyword = \"rust\"").unwrap()];
+
+        let webhook = Webhook::start(&config).unwrap();
+        webhook.send(&[item("Rust \"2\""), item("Weather"), item("rustc")]);
+        drop(webhook);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(requests[0].header("authorization"), Some("Bearer x"));
+        let texts: Vec<_> = bodies(&server).iter().map(|b| b["text"].clone()).collect();
+        assert_eq!(texts, ["Example: Rust \"2\"", "Example: rustc"]);
+    }
+
+    #[test]
+    fn batch_sends_one_array() {
+        let server = Server::start(|_| Response::ok(""));
+        let mut config = config(server.url("/"));
+        config.batch = true;
+        let webhook = Webhook::start(&config).unwrap();
+        webhook.send(&[item("a"), item("b")]);
+        webhook.send(&[]);
+        drop(webhook);
+
+        let bodies = bodies(&server);
+        assert_eq!(bodies.len(), 1);
+        let items: Vec<FeedItem> = serde_json::from_value(bodies[0].clone()).unwrap();
+        assert_eq!(items, [item("a"), item("b")]);
+    }
+
+    #[test]
+    fn retries_server_errors_but_not_client_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let server = Server::start(move |r| match (r.path.as_str(), counter.fetch_add(1, Ordering::SeqCst)) {
+            ("/flaky", 0 | 1) => Response::status(503),
+            ("/flaky", _) => Response::ok(""),
+            _ => Response::status(400),
+        });
+
+        let webhook = Webhook::spawn(&config(server.url("/flaky")), Duration::from_millis(10)).unwrap();
+        webhook.send(&[item("a")]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.requests().len() < 3 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        drop(webhook);
+        assert_eq!(server.requests().len(), 3, "two failures, then success");
+
+        calls.store(0, Ordering::SeqCst);
+        let webhook = Webhook::spawn(&config(server.url("/bad")), Duration::from_millis(10)).unwrap();
+        webhook.send(&[item("a")]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let errors = loop {
+            let errors = webhook.take_errors();
+            if !errors.is_empty() || Instant::now() > deadline {
+                break errors;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "400 is not retried");
+        assert!(errors[0].contains("/bad") && errors[0].contains("400"), "{errors:?}");
+    }
+
+    #[test]
+    fn drop_does_not_wait_for_a_stalled_endpoint() {
+        // Accepts connections (into the backlog) but never answers.
+        let stalled = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", stalled.local_addr().unwrap());
+        let mut webhook = Webhook::start(&config(url)).unwrap();
+        webhook.grace = Duration::from_millis(200);
+        webhook.send(&[item("a"), item("b")]);
+        let start = Instant::now();
+        drop(webhook);
+        assert!(start.elapsed() < Duration::from_secs(5), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn rejects_bad_config() {
+        let mut bad = config("https://example.com/hook".into());
+        bad.template = Some("{title}".into());
+        assert!(Webhook::start(&bad).is_err(), "not JSON");
+        assert!(Webhook::start(&config("not a url".into())).is_err());
+
+        let mut bad = config("https://example.com/hook".into());
+        bad.headers.insert("Bad Name".into(), "x".into());
+        let err = Webhook::start(&bad).err().unwrap();
+        assert!(err.to_string().contains("header name"), "{err:#}");
+        bad.headers = [("X-Token".to_string(), "a\nb".to_string())].into();
+        assert!(Webhook::start(&bad).is_err(), "newline in value");
+    }
+}